@@ -0,0 +1,95 @@
+use std::fmt;
+use std::ops::Bound;
+use std::str::FromStr;
+
+/// The temperature Semantle gives a guess that is not among the answer's
+/// ranked neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Band {
+    /// Below 0.
+    Frigid,
+    /// From 0 up to 20.
+    Cold,
+    /// From 20 up to 30.
+    Tepid,
+    /// From 30 up to 40.
+    Toasty,
+    /// 40 and above.
+    Scalding,
+}
+
+impl Band {
+    pub const ALL: [Band; 5] = [
+        Band::Frigid,
+        Band::Cold,
+        Band::Tepid,
+        Band::Toasty,
+        Band::Scalding,
+    ];
+
+    /// The band a similarity, in points, falls into.
+    pub fn of(similarity: f32) -> Self {
+        if similarity >= 40. {
+            Band::Scalding
+        } else if similarity >= 30. {
+            Band::Toasty
+        } else if similarity >= 20. {
+            Band::Tepid
+        } else if similarity >= 0. {
+            Band::Cold
+        } else {
+            Band::Frigid
+        }
+    }
+
+    /// The similarities, in points, that fall into the band.
+    pub fn range(self) -> (Bound<f32>, Bound<f32>) {
+        use Bound::*;
+        match self {
+            Band::Frigid => (Unbounded, Excluded(0.)),
+            Band::Cold => (Included(0.), Excluded(20.)),
+            Band::Tepid => (Included(20.), Excluded(30.)),
+            Band::Toasty => (Included(30.), Excluded(40.)),
+            Band::Scalding => (Included(40.), Unbounded),
+        }
+    }
+
+    /// The ANSI escape the game colors the band with.
+    pub(crate) fn color(self) -> &'static str {
+        match self {
+            Band::Frigid => "\x1B[34m",
+            Band::Cold => "\x1B[36m",
+            Band::Tepid | Band::Toasty => "\x1B[31m",
+            Band::Scalding => "\x1B[33m",
+        }
+    }
+}
+
+impl FromStr for Band {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "frigid" => Ok(Band::Frigid),
+            "cold" => Ok(Band::Cold),
+            "tepid" => Ok(Band::Tepid),
+            "toasty" => Ok(Band::Toasty),
+            "scalding" => Ok(Band::Scalding),
+            _ => Err(format!(
+                "Unknown band {s}, expected frigid, cold, tepid, toasty or scalding"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Band {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Band::Frigid => "frigid",
+            Band::Cold => "cold",
+            Band::Tepid => "tepid",
+            Band::Toasty => "toasty",
+            Band::Scalding => "scalding",
+        })
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::settings::Settings;
+
+/// Environment variable naming the embeddings file to load.
+pub const EMBEDDINGS_VAR: &str = "SEMANTLE_EMBEDDINGS";
+
+/// Embeddings file used when nothing else is configured.
+pub const DEFAULT_EMBEDDINGS: &str = "./words.bin";
+
+/// The user's config file, `~/.config/semantle/config.toml`.
+///
+/// ```toml
+/// default_model = "google"
+///
+/// [defaults]
+/// clear = true
+///
+/// [models]
+/// google = "/data/GoogleNews-vectors-negative300.bin"
+/// glove = "/data/glove.6B.300d.txt"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub default_model: Option<String>,
+    /// Embeddings files by name, selectable with `--model <name>`.
+    pub models: HashMap<String, PathBuf>,
+    pub defaults: Settings,
+}
+
+impl Config {
+    /// Where the config file lives, honouring `XDG_CONFIG_HOME`.
+    pub fn path() -> Option<PathBuf> {
+        let dir = match env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        };
+        Some(dir.join("semantle").join("config.toml"))
+    }
+
+    /// Loads the config file, or an empty config if there is none.
+    pub fn load() -> Result<Self> {
+        match Self::path() {
+            Some(path) if path.exists() => Self::read(&path),
+            _ => Ok(Config::default()),
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        toml::from_str(&contents).map_err(|source| Error::Config {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Picks the embeddings file for a session. An explicit path wins, then a
+    /// named model, then `SEMANTLE_EMBEDDINGS`, then the configured default
+    /// path or model, then `./words.bin`.
+    pub fn embeddings_path(&self, path: Option<&Path>, model: Option<&str>) -> Result<PathBuf> {
+        if let Some(path) = path {
+            return Ok(path.to_path_buf());
+        }
+        if let Some(model) = model {
+            return self.model(model);
+        }
+        if let Some(path) = env::var_os(EMBEDDINGS_VAR).filter(|path| !path.is_empty()) {
+            return Ok(PathBuf::from(path));
+        }
+        if let Some(path) = &self.defaults.embeddings {
+            return Ok(path.clone());
+        }
+        if let Some(model) = &self.default_model {
+            return self.model(model);
+        }
+        Ok(PathBuf::from(DEFAULT_EMBEDDINGS))
+    }
+
+    fn model(&self, name: &str) -> Result<PathBuf> {
+        self.models
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Error::UnknownModel(name.to_string()))
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::Seek;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use finalfusion::compat::text::ReadText;
+use finalfusion::compat::text::ReadTextDims;
+use finalfusion::compat::word2vec::ReadWord2Vec;
+use finalfusion::io::MmapEmbeddings;
+use finalfusion::prelude::Embeddings;
+use finalfusion::storage::NdArray;
+use finalfusion::storage::StorageView;
+use finalfusion::storage::StorageViewWrap;
+use finalfusion::vocab::SimpleVocab;
+use finalfusion::vocab::Vocab;
+use finalfusion::vocab::VocabWrap;
+use ndarray::s;
+use ndarray::Array2;
+use ndarray::ArrayView2;
+use ndarray::Axis;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::format::EmbeddingFormat;
+
+/// Word vectors stored as the rows of a single matrix. Words are addressed by
+/// their row index.
+///
+/// finalfusion files are memory-mapped, so the matrix is read straight from
+/// the page cache rather than copied into memory.
+pub struct EmbeddingStore {
+    words: Vec<String>,
+    indices: HashMap<String, u32>,
+    storage: StorageViewWrap,
+    format: Option<EmbeddingFormat>,
+    path: Option<PathBuf>,
+    fingerprint: OnceLock<u64>,
+}
+
+impl EmbeddingStore {
+    /// Loads an embeddings file, detecting its format.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::load_format(path, None)
+    }
+
+    /// Loads an embeddings file in the given format, or detects the format if
+    /// none is given.
+    pub fn load_format(path: impl AsRef<Path>, format: Option<EmbeddingFormat>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut file = File::open(path).map_err(|e| Error::io(path, e))?;
+        let format = match format {
+            Some(format) => format,
+            None => {
+                let format = EmbeddingFormat::detect(&mut BufReader::new(&file))
+                    .map_err(|e| Error::io(path, e))?;
+                file.rewind().map_err(|e| Error::io(path, e))?;
+                format.ok_or_else(|| Error::UnknownFormat(path.to_path_buf()))?
+            }
+        };
+        let mut reader = BufReader::new(file);
+        let invalid = |source| Error::Embeddings {
+            path: path.to_path_buf(),
+            format,
+            source,
+        };
+
+        let store = if format == EmbeddingFormat::FinalFusion {
+            let embeddings: Embeddings<VocabWrap, StorageViewWrap> =
+                Embeddings::mmap_embeddings(&mut reader).map_err(invalid)?;
+            // Mapping past the end of a truncated file would fault on access,
+            // so make sure the matrix ends inside the file.
+            let len = reader
+                .get_ref()
+                .metadata()
+                .map_err(|e| Error::io(path, e))?
+                .len();
+            let end = reader.stream_position().map_err(|e| Error::io(path, e))?;
+            if end > len {
+                return Err(invalid(finalfusion::error::Error::Format(format!(
+                    "file is truncated: {len} bytes, expected at least {end}"
+                ))));
+            }
+            let (_, vocab, storage, _) = embeddings.into_parts();
+            if vocab.words_len() > storage.view().nrows() {
+                return Err(invalid(finalfusion::error::Error::Format(
+                    "storage has fewer rows than the vocabulary has words".to_string(),
+                )));
+            }
+            Self::from_storage(vocab.words().to_vec(), storage)
+        } else {
+            let embeddings: Embeddings<SimpleVocab, NdArray> = match format {
+                EmbeddingFormat::Word2VecBinary => {
+                    Embeddings::read_word2vec_binary_lossy(&mut reader)
+                }
+                EmbeddingFormat::TextDims => Embeddings::read_text_dims_lossy(&mut reader),
+                EmbeddingFormat::Text => Embeddings::read_text_lossy(&mut reader),
+                EmbeddingFormat::FinalFusion => unreachable!(),
+            }
+            .map_err(invalid)?;
+            let (_, vocab, storage, _) = embeddings.into_parts();
+            Self::from_parts(vocab.words().to_vec(), storage.into())?
+        };
+        if store.is_empty() {
+            return Err(Error::Empty(path.to_path_buf()));
+        }
+        Ok(EmbeddingStore {
+            format: Some(format),
+            path: Some(path.to_path_buf()),
+            ..store
+        })
+    }
+
+    /// Builds a store from words and a matrix holding one row per word.
+    pub fn from_parts(words: Vec<String>, matrix: Array2<f32>) -> Result<Self> {
+        if words.len() != matrix.nrows() {
+            return Err(Error::Shape {
+                words: words.len(),
+                rows: matrix.nrows(),
+            });
+        }
+        let matrix = if matrix.is_standard_layout() {
+            matrix
+        } else {
+            matrix.as_standard_layout().into_owned()
+        };
+        Ok(Self::from_storage(words, NdArray::from(matrix).into()))
+    }
+
+    /// Builds a store over finalfusion storage whose first rows belong to
+    /// `words`. Subword vocabularies keep their n-gram buckets after the
+    /// words; those rows are never used.
+    fn from_storage(words: Vec<String>, storage: StorageViewWrap) -> Self {
+        assert!(words.len() <= storage.view().nrows());
+        let indices = words
+            .iter()
+            .enumerate()
+            .map(|(index, word)| (word.clone(), index as u32))
+            .collect();
+        EmbeddingStore {
+            words,
+            indices,
+            storage,
+            format: None,
+            path: None,
+            fingerprint: OnceLock::new(),
+        }
+    }
+
+    /// The format the store was loaded from, if it came from a file.
+    pub fn format(&self) -> Option<EmbeddingFormat> {
+        self.format
+    }
+
+    /// A 64-bit FNV-1a hash of the words in order, to tell vocabularies
+    /// apart. It is the same on every platform and run.
+    pub fn fingerprint(&self) -> u64 {
+        *self.fingerprint.get_or_init(|| {
+            self.words
+                .iter()
+                .flat_map(|word| word.bytes().chain([b'\n']))
+                .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                    (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+                })
+        })
+    }
+
+    /// The file the store was loaded from.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn index(&self, word: &str) -> Option<u32> {
+        self.indices.get(word).copied()
+    }
+
+    pub fn word(&self, index: u32) -> &str {
+        &self.words[index as usize]
+    }
+
+    pub fn vector(&self, index: u32) -> &[f32] {
+        self.matrix()
+            .index_axis_move(Axis(0), index as usize)
+            .to_slice()
+            .expect("rows are contiguous")
+    }
+
+    pub fn get(&self, word: &str) -> Option<&[f32]> {
+        self.index(word).map(|index| self.vector(index))
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.indices.contains_key(word)
+    }
+
+    pub fn matrix(&self) -> ArrayView2<'_, f32> {
+        self.storage.view().slice_move(s![..self.words.len(), ..])
+    }
+
+    pub fn dims(&self) -> usize {
+        self.storage.view().ncols()
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Every index in the store, in vocabulary order.
+    pub fn indices(&self) -> impl Iterator<Item = u32> {
+        0..self.words.len() as u32
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[f32])> {
+        self.indices()
+            .map(|index| (self.word(index), self.vector(index)))
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use crate::format::EmbeddingFormat;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Why embeddings or configuration could not be loaded.
+#[derive(Debug)]
+pub enum Error {
+    /// The embeddings file does not exist.
+    NotFound(PathBuf),
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// The file is not in any format that can be detected.
+    UnknownFormat(PathBuf),
+    /// finalfusion rejected the file: it is truncated, corrupt, of an
+    /// unsupported version, or not in the given format.
+    Embeddings {
+        path: PathBuf,
+        format: EmbeddingFormat,
+        source: finalfusion::error::Error,
+    },
+    /// The file loaded but has no words in it.
+    Empty(PathBuf),
+    /// Words and matrix rows given to
+    /// [`EmbeddingStore::from_parts`](crate::EmbeddingStore::from_parts)
+    /// don't pair up.
+    Shape {
+        words: usize,
+        rows: usize,
+    },
+    Config {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    /// A model name that is not in the config file's registry.
+    UnknownModel(String),
+}
+
+impl Error {
+    pub(crate) fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        let path = path.into();
+        if source.kind() == io::ErrorKind::NotFound {
+            Error::NotFound(path)
+        } else {
+            Error::Io { path, source }
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFound(path) => {
+                write!(f, "embedding file not found at {}", path.display())
+            }
+            Error::Io { path, source } => write!(f, "cannot read {}: {source}", path.display()),
+            Error::UnknownFormat(path) => write!(
+                f,
+                "unsupported format in {}; pass --format with one of {}",
+                path.display(),
+                EmbeddingFormat::NAMES
+            ),
+            Error::Embeddings {
+                path,
+                format,
+                source,
+            } => write!(
+                f,
+                "cannot load {} as {format} embeddings (unsupported format/version or corrupt file): {source}",
+                path.display()
+            ),
+            Error::Empty(path) => write!(f, "no words in {}", path.display()),
+            Error::Shape { words, rows } => {
+                write!(f, "{words} words but {rows} rows of embeddings")
+            }
+            Error::Config { path, source } => {
+                write!(f, "invalid config file {}: {source}", path.display())
+            }
+            Error::UnknownModel(name) => write!(f, "unknown model {name}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Embeddings { source, .. } => Some(source),
+            Error::Config { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::fmt;
+use std::io;
+use std::io::BufRead;
+use std::io::Read;
+use std::str::FromStr;
+
+use serde::de;
+use serde::Deserialize;
+use serde::Deserializer;
+
+/// The on-disk layouts an [`EmbeddingStore`](crate::EmbeddingStore) can be
+/// loaded from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddingFormat {
+    /// finalfusion (`.fifu`) files.
+    FinalFusion,
+    /// The binary format of the original word2vec tool, as used by the
+    /// GoogleNews model.
+    Word2VecBinary,
+    /// Text with a `<words> <dims>` header line, as written by word2vec and
+    /// by fastText (`.vec`).
+    TextDims,
+    /// Text without a header line, as distributed by GloVe.
+    Text,
+}
+
+impl EmbeddingFormat {
+    pub const NAMES: &'static str = "finalfusion, word2vec, word2vec-text, fasttext, glove";
+
+    /// Guesses the format from the start of a file.
+    ///
+    /// finalfusion files start with a magic number. A first line of two
+    /// integers is a word2vec-style header, and the line after it tells
+    /// binary and text apart. Anything else whose first line is a word
+    /// followed by numbers is taken to be GloVe text.
+    pub fn detect(reader: &mut impl BufRead) -> io::Result<Option<Self>> {
+        let first = read_line(reader)?;
+        if first.starts_with(b"FiFu") {
+            return Ok(Some(EmbeddingFormat::FinalFusion));
+        }
+        let first = String::from_utf8_lossy(&first);
+        let header = first
+            .split_whitespace()
+            .map(|term| term.parse::<usize>())
+            .collect::<Result<Vec<_>, _>>();
+        if let Ok([_, dims]) = header.as_deref() {
+            let second = read_line(reader)?;
+            let is_text = std::str::from_utf8(&second)
+                .map(|line| is_vector_line(line, Some(*dims)))
+                .unwrap_or(false);
+            return Ok(Some(if is_text {
+                EmbeddingFormat::TextDims
+            } else {
+                EmbeddingFormat::Word2VecBinary
+            }));
+        }
+        Ok(is_vector_line(&first, None).then_some(EmbeddingFormat::Text))
+    }
+}
+
+/// Reads up to the next newline, giving up on lines too long to be a
+/// reasonable embedding.
+fn read_line(reader: &mut impl BufRead) -> io::Result<Vec<u8>> {
+    let mut line = Vec::new();
+    reader.take(1 << 20).read_until(b'\n', &mut line)?;
+    Ok(line)
+}
+
+/// Whether `line` is a word followed by `dims` numbers, or by at least one
+/// number if `dims` is not known.
+fn is_vector_line(line: &str, dims: Option<usize>) -> bool {
+    let mut terms = line.split_whitespace();
+    if terms.next().is_none() {
+        return false;
+    }
+    let mut count = 0;
+    for term in terms {
+        if term.parse::<f32>().is_err() {
+            return false;
+        }
+        count += 1;
+    }
+    match dims {
+        Some(dims) => count == dims,
+        None => count > 0,
+    }
+}
+
+impl FromStr for EmbeddingFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "finalfusion" | "fifu" => Ok(EmbeddingFormat::FinalFusion),
+            "word2vec" | "w2v" => Ok(EmbeddingFormat::Word2VecBinary),
+            "word2vec-text" | "fasttext" | "vec" => Ok(EmbeddingFormat::TextDims),
+            "glove" | "text" => Ok(EmbeddingFormat::Text),
+            _ => Err(format!(
+                "Unknown embedding format {s}, expected one of {}",
+                Self::NAMES
+            )),
+        }
+    }
+}
+
+impl fmt::Display for EmbeddingFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EmbeddingFormat::FinalFusion => "finalfusion",
+            EmbeddingFormat::Word2VecBinary => "word2vec",
+            EmbeddingFormat::TextDims => "word2vec-text",
+            EmbeddingFormat::Text => "glove",
+        })
+    }
+}
+
+/// Spelled the same way as the `--format` flag.
+impl<'de> Deserialize<'de> for EmbeddingFormat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn detect(bytes: &[u8]) -> Option<EmbeddingFormat> {
+        EmbeddingFormat::detect(&mut Cursor::new(bytes)).unwrap()
+    }
+
+    #[test]
+    fn detects_formats() {
+        assert_eq!(
+            detect(b"FiFu\x01\x00\x00\x00"),
+            Some(EmbeddingFormat::FinalFusion)
+        );
+        assert_eq!(
+            detect(b"2 3\napple 0.1 0.2 0.3\nbanana 0.4 0.5 0.6\n"),
+            Some(EmbeddingFormat::TextDims)
+        );
+        assert_eq!(
+            detect(b"2 3\napple \xcd\xcc\xcc\x3d\xcd\xcc\x4c\x3e\x9a\x99\x99\x3e"),
+            Some(EmbeddingFormat::Word2VecBinary)
+        );
+        assert_eq!(
+            detect(b"apple 0.1 0.2 0.3\nbanana 0.4 0.5 0.6\n"),
+            Some(EmbeddingFormat::Text)
+        );
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert_eq!(detect(b""), None);
+        assert_eq!(detect(b"hello world\n"), None);
+        assert_eq!(detect(b"apple\n"), None);
+    }
+
+    #[test]
+    fn parses_names() {
+        for name in [
+            "finalfusion",
+            "word2vec",
+            "word2vec-text",
+            "fasttext",
+            "glove",
+        ] {
+            let format = name.parse::<EmbeddingFormat>().unwrap();
+            assert_eq!(format.to_string().parse::<EmbeddingFormat>(), Ok(format));
+        }
+        assert!("bert".parse::<EmbeddingFormat>().is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::HashSet;
+
+use rand::Rng;
+
+use crate::embeddings::EmbeddingStore;
+use crate::settings::Settings;
+
+/// A word the player has guessed, numbered in the order it was entered.
+pub struct Guess {
+    pub number: usize,
+    pub word: String,
+    pub similarity: f32,
+    /// Position of the word when the vocabulary is sorted by similarity to
+    /// the answer; the answer itself is 0.
+    pub index: usize,
+    /// Semantle's "N/1000" rank, for words among the answer's nearest
+    /// neighbors.
+    pub rank: Option<usize>,
+}
+
+pub enum GuessOutcome {
+    /// The answer was found after this many guesses.
+    Found(usize),
+    /// The word is in the log under this guess number.
+    Known(usize),
+    Unknown,
+}
+
+/// A round of Semantle against a randomly chosen answer.
+pub struct Game<'a> {
+    store: &'a EmbeddingStore,
+    answer: u32,
+    /// Every word ordered from most to least similar to the answer.
+    most_similar: Vec<u32>,
+    /// Rounded similarity and position in `most_similar`, indexed like the
+    /// store.
+    similarities: Vec<(f32, usize)>,
+    guesses: Vec<Guess>,
+    guessed: HashSet<String>,
+    best_guessed: usize,
+    rank_threshold: usize,
+}
+
+impl<'a> Game<'a> {
+    pub fn new(store: &'a EmbeddingStore, settings: &Settings, rng: &mut impl Rng) -> Self {
+        let answer = rng.gen_range(0..store.len() as u32);
+        let rounded = store
+            .similarities_to(answer)
+            .mapv(|sim| (10000. * sim).round() / 100.);
+        let mut most_similar = store.indices().collect::<Vec<_>>();
+        most_similar.sort_by(|&a, &b| rounded[b as usize].total_cmp(&rounded[a as usize]));
+        let mut similarities = vec![(0., 0); store.len()];
+        most_similar.iter().enumerate().for_each(|(index, &word)| {
+            similarities[word as usize] = (rounded[word as usize], index);
+        });
+        Game {
+            store,
+            answer,
+            most_similar,
+            similarities,
+            guesses: Vec::new(),
+            guessed: HashSet::new(),
+            best_guessed: 0,
+            rank_threshold: settings.rank_threshold.clamp(1, store.len()),
+        }
+    }
+
+    pub fn answer(&self) -> &'a str {
+        self.store.word(self.answer)
+    }
+
+    /// How many of the answer's neighbors get a rank, the setting clamped to
+    /// the vocabulary.
+    pub fn rank_threshold(&self) -> usize {
+        self.rank_threshold
+    }
+
+    pub fn guesses(&self) -> &[Guess] {
+        &self.guesses
+    }
+
+    pub fn guess(&mut self, word: &str) -> GuessOutcome {
+        let Some(guess) = self.store.index(word) else {
+            return GuessOutcome::Unknown;
+        };
+        if guess == self.answer {
+            return GuessOutcome::Found(self.guesses.len() + 1);
+        }
+        let (similarity, index) = self.similarities[guess as usize];
+        if !self.guessed.insert(word.to_string()) {
+            let number = self.guesses.iter().find(|i| i.word == word).unwrap().number;
+            return GuessOutcome::Known(number);
+        }
+        let number = self.guesses.len() + 1;
+        let rank = (index < self.rank_threshold).then(|| self.rank_threshold - index);
+        if let Some(rank) = rank {
+            self.best_guessed = self.best_guessed.max(rank);
+        }
+        self.guesses.push(Guess {
+            number,
+            word: word.to_string(),
+            similarity,
+            index,
+            rank,
+        });
+        GuessOutcome::Known(number)
+    }
+
+    /// A word ranked just above the best guess so far.
+    pub fn hint(&self) -> &'a str {
+        let hint = self
+            .most_similar
+            .get(self.rank_threshold - self.best_guessed - 1)
+            .unwrap();
+        self.store.word(*hint)
+    }
+}
@@ -0,0 +1,23 @@
+//! Semantle solver and game engine.
+//!
+//! The [`Solver`] narrows down the hidden word from logged similarities, and
+//! the [`Game`] plays a round of Semantle against a random word. Both borrow
+//! their vectors from an [`EmbeddingStore`].
+
+pub mod band;
+pub mod config;
+pub mod embeddings;
+pub mod error;
+pub mod format;
+pub mod game;
+pub mod render;
+pub mod settings;
+pub mod similarity;
+pub mod solver;
+
+pub use embeddings::EmbeddingStore;
+pub use error::Error;
+pub use error::Result;
+pub use game::Game;
+pub use settings::Settings;
+pub use solver::Solver;
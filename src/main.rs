@@ -1,852 +1,399 @@
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::collections::HashSet;
-use std::env;
+use std::fmt::Display;
 use std::fs::File;
 use std::io;
+use std::io::BufRead;
 use std::io::BufReader;
+use std::io::IsTerminal;
 use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
 use std::process::exit;
-use std::rc::Rc;
+use std::process::ExitCode;
+use std::time::Instant;
 
-use finalfusion::io::ReadEmbeddings;
-use finalfusion::prelude::Embeddings;
-use finalfusion::storage::StorageViewWrap;
-use finalfusion::vocab::SimpleVocab;
-use finalfusion::vocab::Vocab;
+use clap::Parser;
+use clap::Subcommand;
 use itertools::Itertools;
-use rand::thread_rng;
+use rand::rngs::StdRng;
 use rand::Rng;
+use rand::SeedableRng;
+use rayon::ThreadPoolBuilder;
 use rustyline::config::Builder;
 use rustyline::history::MemHistory;
 use rustyline::Editor;
+use semantle::config::Config;
+use semantle::format::EmbeddingFormat;
+use semantle::game::GuessOutcome;
+use semantle::render::Board;
+use semantle::settings::parse_tolerance;
+use semantle::settings::ColorChoice;
+use semantle::solver::CommandOutput;
+use semantle::EmbeddingStore;
+use semantle::Game;
+use semantle::Settings;
+use semantle::Solver;
 
-type Func<'a> = dyn Fn(
-    Vec<&str>,
-    HashMap<&'a str, Vec<f32>>,
-    HashMap<&'a str, Vec<f32>>,
-    Vec<(String, f32)>,
-    &'a str,
-    Vec<Command>,
-) -> (Option<i32>, HashMap<&'a str, Vec<f32>>, Vec<(String, f32)>);
+/// Solve or play Semantle with word embeddings.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Embeddings file to load [default: $SEMANTLE_EMBEDDINGS, the config file's default model, or ./words.bin]
+    #[arg(long, global = true, value_name = "PATH", conflicts_with = "model")]
+    embeddings: Option<PathBuf>,
+    /// Model to load from the config file's registry
+    #[arg(long, global = true, value_name = "NAME")]
+    model: Option<String>,
+    /// Format of the embeddings file (finalfusion, word2vec, word2vec-text, fasttext, glove) [default: detected]
+    #[arg(long, global = true, value_name = "FORMAT")]
+    format: Option<EmbeddingFormat>,
+    /// How many of the answer's nearest neighbors get an "N/1000" rank [default: 1000]
+    #[arg(long, global = true, value_name = "N")]
+    rank_threshold: Option<usize>,
+    /// Threads to spread the solver's work over [default: one per core]
+    #[arg(long, global = true, value_name = "N")]
+    threads: Option<usize>,
+    #[command(subcommand)]
+    command: Command,
+}
 
-struct Command<'a> {
-    command: &'a str,
-    usage: &'a str,
-    description: &'a str,
-    run: Box<Func<'a>>,
+#[derive(Subcommand)]
+enum Command {
+    /// Narrow down the answer to a Semantle puzzle from your guesses
+    Solve {
+        /// Clear the screen after every command
+        #[arg(long, overrides_with = "no_clear")]
+        clear: bool,
+        /// Don't clear the screen, even if the config file says to
+        #[arg(long)]
+        no_clear: bool,
+        /// How far a similarity may be from a logged value and still match it [default: 0.005]
+        #[arg(long, value_name = "POINTS", value_parser = parse_tolerance, allow_negative_numbers = true)]
+        tolerance: Option<f32>,
+        /// Resume the session saved in this file, and save to it after every command
+        #[arg(long, value_name = "FILE")]
+        session: Option<PathBuf>,
+        /// Run the commands in this file without prompts, stopping at the first error. Commands piped on stdin run the same way
+        #[arg(long, value_name = "FILE")]
+        script: Option<PathBuf>,
+        /// Print every command's output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Play Semantle against a random word
+    Play {
+        /// Seed for picking the answer, to replay the same word
+        #[arg(long)]
+        seed: Option<u64>,
+        /// When to color guesses by how close they are (auto, always, never) [default: auto]
+        #[arg(long, value_name = "WHEN")]
+        color: Option<ColorChoice>,
+    },
+    /// List the models in the config file
+    Models,
+    /// Load the embeddings and describe them
+    Info,
 }
 
-fn main() {
-    let args = env::args().collect::<Vec<_>>();
-    match args.len() {
-        0 => println!("Invalid mode"),
-        1 => println!("Usage: {} <solve [--clear]|play>", args.get(0).unwrap()),
-        2 => {
-            let path = args.get(0).unwrap();
-            let mode = args.get(1).unwrap();
-            match mode.as_str() {
-                "solve" => start_solver(false),
-                "play" => start_game(),
-                _ => println!("Usage: {} <solve|play>", path),
-            }
+impl Cli {
+    /// Overrides the config file's defaults with the flags that were given.
+    fn apply(&self, settings: &mut Settings) {
+        if let Some(format) = self.format {
+            settings.format = Some(format);
+        }
+        if let Some(rank_threshold) = self.rank_threshold {
+            settings.rank_threshold = rank_threshold;
+        }
+        if let Some(threads) = self.threads {
+            settings.threads = Some(threads);
         }
-        3 => {
-            if args.get(1).unwrap() != "solve" && args.get(2).unwrap() != "--clear" { println!("Usage: {} <solve|play>", args.get(0).unwrap()); }
-            else { start_solver(true); }
+        match self.command {
+            Command::Solve {
+                clear,
+                no_clear,
+                tolerance,
+                json,
+                ..
+            } => {
+                if clear || no_clear {
+                    settings.clear = clear;
+                }
+                if let Some(tolerance) = tolerance {
+                    settings.tolerance = tolerance;
+                }
+                if json {
+                    settings.json = true;
+                }
+            }
+            Command::Play { color, .. } => {
+                if let Some(color) = color {
+                    settings.color = color;
+                }
+            }
+            Command::Models | Command::Info => {}
         }
-        _ => println!("Usage: {} <solve|play>", args.get(0).unwrap()),
     }
 }
 
-fn start_solver(clear: bool) {
-    println!("Loading...");
-    let mut reader = BufReader::new(File::open("./words.bin").unwrap());
-
-    let embeddings: &mut Embeddings<SimpleVocab, StorageViewWrap> =
-        Box::<Embeddings<_, _>>::leak(Box::new(Embeddings::read_embeddings(&mut reader).unwrap()));
-    let mut words_to_vecs = HashMap::new();
-    for word in embeddings.vocab().words().iter() {
-        words_to_vecs.insert(word.as_str(), embeddings.embedding(word).unwrap().to_vec());
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let config = Config::load().unwrap_or_else(|e| fail(e));
+    if let Command::Models = cli.command {
+        return list_models(&config);
     }
-    let original_words = words_to_vecs.clone();
-    let mut log = Vec::new();
-    let mut rl: Editor<(), MemHistory> = Editor::with_history(
-        Builder::new().auto_add_history(true).build(),
-        MemHistory::new(),
-    )
-    .unwrap();
-    let mut commands = HashMap::new();
-    let command_vec = init_commands();
-    for c in command_vec.iter() {
-        commands.insert(c.command, c);
+    let path = config
+        .embeddings_path(cli.embeddings.as_deref(), cli.model.as_deref())
+        .unwrap_or_else(|e| fail(e));
+    let mut settings = config.defaults.clone();
+    cli.apply(&mut settings);
+    if let Some(threads) = settings.threads {
+        ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .unwrap_or_else(|e| fail(e));
     }
-    if clear { print!("\x1B[2J\x1B[1;1H"); }
-    let _ = io::stdout().flush();
-    println!("Ready! Type a valid command or type h for help.");
-    loop {
-        let line = rl.readline("semantle> ");
-        if clear { print!("\x1B[2J\x1B[1;1H"); }
-        let _ = io::stdout().flush();
-        if line.is_err() {
-            exit(0);
+    let store = load(&path, settings.format);
+    match cli.command {
+        Command::Solve {
+            session, script, ..
+        } => {
+            let input = match script {
+                Some(script) => {
+                    let file = File::open(&script).unwrap_or_else(|e| fail(e));
+                    Input::Script(Box::new(BufReader::new(file)))
+                }
+                None if !io::stdin().is_terminal() => Input::Script(Box::new(io::stdin().lock())),
+                None => Input::Terminal(Box::new(editor())),
+            };
+            start_solver(&store, settings, session.as_deref(), input)
         }
-        let command = Rc::new(RefCell::new(
-            line.unwrap().to_lowercase().trim().to_string(),
-        ));
-        if command.borrow().is_empty() {
-            continue;
+        Command::Play { seed, .. } => {
+            let mut rng = match seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+            start_game(&store, &settings, &mut rng)
         }
-        let t = (*command).borrow().to_owned();
-        let terms = t.split(' ').collect::<Vec<_>>();
-        let command = terms.first().unwrap();
-
-        let func = commands.get(command);
-
-        match func {
-            None => println!("Unknown command, please try again."),
-            Some(x) => {
-                let exit_code;
-                (exit_code, words_to_vecs, log) = (x.run)(
-                    terms.clone(),
-                    original_words.clone(),
-                    words_to_vecs.clone(),
-                    log.clone(),
-                    x.usage,
-                    init_commands(),
-                );
-                if let Some(code) = exit_code {
-                    exit(code);
-                }
+        Command::Info => {
+            println!("Path:       {}", path.display());
+            if let Some(format) = store.format() {
+                println!("Format:     {format}");
             }
+            println!("Words:      {}", store.len());
+            println!("Dimensions: {}", store.dims());
+            ExitCode::SUCCESS
         }
+        Command::Models => unreachable!(),
     }
 }
 
-fn init_commands() -> Vec<Command<'static>> {
-    let mut commands = vec![
-        Command {
-            command: "w",
-            usage: "w <word> <value|-r|value -e>",
-            description: "Add a word with its similarity, edit an existing word's similarity, or remove a word",
-            run: Box::new(|params: Vec<&str>, original_words: HashMap<&str, Vec<f32>>, mut words_to_vecs: HashMap<&str, Vec<f32>>, mut log: Vec<(String, f32)>, usage: &str, _: Vec<Command>,| {
-                let params = params.into_iter().skip(1);
-                let mut state = AddWordState::Normal;
-                let mut word_count = 0;
-                let mut word = None;
-                let mut val = None;
-                for term in params {
-                    match term {
-                        "-n" => state = AddWordState::Normal,
-                        "-e" => state = AddWordState::Edit,
-                        "-r" => state = AddWordState::Remove,
-                        x => match word_count {
-                            0 => {
-                                if !original_words.contains_key(x) {
-                                    println!("Unknown word {}", x);
-                                    return (None, words_to_vecs, log);
-                                }
-                                word = Some(x.to_string());
-                                word_count += 1;
-                            }
-                            1 => match term.parse::<f32>() {
-                                Err(_) => {
-                                    println!("Usage: {usage}");
-                                    return (None, words_to_vecs, log);
-                                }
-                                Ok(y) => {
-                                    val = Some(y);
-                                    word_count += 1;
-                                }
-                            },
-                            _ => {
-                                println!("Usage: {usage}");
-                                return (None, words_to_vecs, log);
-                            }
-                        },
-                    }
-                }
-                if word.is_none() {
-                    println!("Usage: {usage}");
-                    return (None, words_to_vecs, log);
-                }
-                let word = word.unwrap();
-                match state {
-                    AddWordState::Normal => {
-                        if val.is_none() {
-                            println!("Usage: {usage}");
-                            return (None, words_to_vecs, log);
-                        }
-                        if log.iter().any(|(a, _)| *a == word) {
-                            println!("This word already has a value. Try using -e to change an existing value.");
-                            return (None, words_to_vecs, log);
-                        }
-                        words_to_vecs.retain(|_, value| {
-                            filter_embeddings(
-                                original_words.get(word.as_str()).unwrap(),
-                                value.as_slice(),
-                                val.unwrap(),
-                            )
-                        });
-                        log.push((word, val.unwrap()));
-                    }
-                    AddWordState::Edit => {
-                        if val.is_none() || !log.iter().any(|(a, _)| *a == word) {
-                            println!("Usage: {usage}");
-                            return (None, words_to_vecs, log);
-                        }
-                        log = log
-                            .into_iter()
-                            .map(|(a, b)| (a.clone(), if *a == word { val.unwrap() } else { b }))
-                            .collect();
-                        update_words(&original_words, &mut words_to_vecs, &log);
-                    }
-                    AddWordState::Remove => {
-                        if !log.iter().any(|(a, _)| *a == word) {
-                            println!("Usage: {usage}");
-                            return (None, words_to_vecs, log);
-                        }
-                        log.retain(|(a, _)| *a != word);
-                        update_words(&original_words, &mut words_to_vecs, &log);
-                    }
-                }
-                (None, words_to_vecs, log)
-            })
-        },
-        Command {
-            command: "l",
-            usage: "l [-d]",
-            description: "List the guessed words with their similarities in human-readable or debug mode",
-            run: Box::new(|params: Vec<&str>, _original_words: HashMap<&str, Vec<f32>>, words_to_vecs: HashMap<&str, Vec<f32>>, log: Vec<(String, f32)>, usage: &str, _: Vec<Command>,| {
-                let mut params = params.into_iter().skip(1);
-                match params.next() {
-                    None => {
-                        println!("Here are the words and similarities you've provided so far:");
-                        log.iter().enumerate().for_each(|(i, (a, b))| {
-                            println!("\t{}. `{}` with a similarity of `{}`", i + 1, a, b)
-                        });
-                        (None, words_to_vecs, log)
-                    }
-                    Some("-d") => match params.next() {
-                        None => {
-                            println!("{:?}", log);
-                            (None, words_to_vecs, log)
-                        }
-                        Some(_) => {
-                            println!("Usage: {usage}");
-                            (None, words_to_vecs, log)
-                        }
-                    },
-                    Some(_) => {
-                        println!("Usage: {usage}");
-                        (None, words_to_vecs, log)
-                    }
-                }
-            })
-        },
-        Command {
-            command: "p",
-            usage: "p",
-            description: "View remaining possible words",
-            run: Box::new(|params: Vec<&str>, _original_words: HashMap<&str, Vec<f32>>, words_to_vecs: HashMap<&str, Vec<f32>>, log: Vec<(String, f32)>, usage: &str, _: Vec<Command>,| {
-                let params = params.into_iter().skip(1);
-                let mut debug_mode = false;
-                let mut show_embeddings = false;
-                for term in params {
-                    match term {
-                        "-d" => {
-                            debug_mode = true;
-                        }
-                        "-e" => {
-                            debug_mode = true;
-                            show_embeddings = true;
-                        }
-                        _ => {
-                            println!("Usage: {usage}");
-                            return (None, words_to_vecs, log);
-                        }
-                    }
-                }
-                match debug_mode {
-                    true => {
-                        if show_embeddings {
-                            println!("{:?}", words_to_vecs);
-                        } else {
-                            println!("{:?}", words_to_vecs.keys());
-                        }
-                    }
-                    false => {
-                        words_to_vecs.keys().for_each(|k| println!("{}", k));
-                    }
-                }
-                (None, words_to_vecs, log)
-            })
-        },
-        Command {
-            command: "q",
-            usage: "q",
-            description: "Quit",
-            run: Box::new(|params: Vec<&str>, _original_words: HashMap<&str, Vec<f32>>, words_to_vecs: HashMap<&str, Vec<f32>>, log: Vec<(String, f32)>, usage: &str, _: Vec<Command>,| {
-                let mut params = params.into_iter().skip(1);
-                if params.next().is_some() {
-                    println!("Usage: {usage}");
-                    return (None, words_to_vecs, log);
-                }
-                (Some(0), words_to_vecs, log)
-            })
-        },
-        Command {
-            command: "h",
-            usage: "h",
-            description: "Display this help message",
-            run: Box::new(|params: Vec<&str>, _original_words: HashMap<&str, Vec<f32>>, words_to_vecs: HashMap<&str, Vec<f32>>, log: Vec<(String, f32)>, usage: &str, commands: Vec<Command>,| {
-                let mut params = params.into_iter().skip(1);
-                if params.next().is_some() {
-                    println!("Usage: {usage}");
-                    return (None, words_to_vecs, log);
-                }
-                println!("Type one of the following commands:");
-                commands.iter().for_each(|a| { println!("\t{}", a.usage); println!("\t\t{}", a.description) });
-                (None, words_to_vecs, log)
-            })
-        },
-        Command {
-            command: "fb",
-            usage: "fb",
-            description: "Find the best word according to current information",
-            run: Box::new(|params: Vec<&str>, _original_words: HashMap<&str, Vec<f32>>, words_to_vecs: HashMap<&str, Vec<f32>>, log: Vec<(String, f32)>, usage: &str, _: Vec<Command>,| {
-                let mut params = params.into_iter().skip(1);
-                if params.next().is_some() {
-                    println!("Usage: {usage}");
-                    return (None, words_to_vecs, log);
-                }
-                if log.is_empty() {
-                    println!("The optimal word based on your current information is eget",);
-                    return (None, words_to_vecs, log);
-                }
-                let best = words_to_vecs
-                    .iter()
-                    .map(|(a, b)| {
-                        (
-                            a,
-                            words_to_vecs
-                                .values()
-                                .map(|d| (dot_product(b, d) * 10000.).round() as i32)
-                                .unique()
-                                .count(),
-                        )
-                    })
-                    .fold(("-", 0), |a, b| (b.0, a.1.max(b.1)));
-                println!(
-                    "The optimal word based on your current information is {}",
-                    best.0
-                );
-                (None, words_to_vecs, log)
-            })
-        },
-        Command {
-            command: "c",
-            usage: "c <length> <word> [-dr]",
-            description: "List the <length> closest words to <word>, optionally in debug mode and/or in reverse",
-            run: Box::new(|params: Vec<&str>, original_words: HashMap<&str, Vec<f32>>, words_to_vecs: HashMap<&str, Vec<f32>>, log: Vec<(String, f32)>, usage: &str, _: Vec<Command>,| {
-                let params = params.into_iter().skip(1);
-                let mut idx = 0;
-                let mut length = 0;
-                let mut word = "-";
-                let mut rev = false;
-                let mut debug = false;
-                for term in params {
-                    match (term, idx) {
-                        (_,0) => {
-                            if let Ok(y) = term.parse::<usize>() {
-                                length = y;
-                                idx += 1;
-                            } else {
-                                println!("Usage: {usage}");
-                                return (None, words_to_vecs, log);
-                            }
-                        }
-                        (y,1) => {
-                            word = y;
-                            idx += 1;
-                        }
-                        ("-d",_) => {
-                            debug = !debug;
-                        }
-                        ("-r",_) => {
-                            rev = !rev;
-                        }
-                        _ => {
-                            println!("Usage: {usage}");
-                            return (None, words_to_vecs, log);
-                        }
-                    }
-                }
-                if idx < 2 {
-                    println!("Usage: {usage}");
-                    return (None, words_to_vecs, log);
-                }
-                let sim = original_words.get(word);
-                if sim.is_none() {
-                    println!("Unknown word {word}");
-                    return (None, words_to_vecs, log);
-                }
-                let sim = sim.unwrap();
-                let mut orig_words_by_word = original_words
-                    .iter()
-                    .filter(|(a,_)| **a != word)
-                    .map(|(a, b)| {
-                        (
-                            a,
-                            dot_product(b, sim),
-                        )
-                    }).collect::<Vec<_>>();
-                orig_words_by_word.sort_by(|(_,a),(_,b)| a.total_cmp(b));
-                orig_words_by_word.reverse();
-                match (debug, rev) {
-                    (false, false) => {
-                        let top_n = orig_words_by_word.iter().take(length);
-                        let spaces1 = (length+1).to_string().len();
-                        let spaces2 = orig_words_by_word.iter().fold(("", 0), |a, b| (b.0, a.1.max(b.0.chars().count()))).1;
-                        top_n.enumerate().for_each(|(index, (word, sim))| println!("{}{}{word}{}{sim}", index+1, " ".repeat(spaces1 - (index+1).to_string().len() + 1), " ".repeat(spaces2 - word.chars().count() + 1)));
-                    }
-                    (false, true) => {
-                        let top_n = orig_words_by_word.iter().take(length).rev();
-                        let spaces1 = (length+1).to_string().len();
-                        let spaces2 = orig_words_by_word.iter().fold(("", 0), |a, b| (b.0, a.1.max(b.0.len()))).1;
-                        top_n.enumerate().for_each(|(index, (word, sim))| println!("{}{}{word}{}{sim}", length-index, " ".repeat(spaces1 - (length-index).to_string().len() + 1), " ".repeat(spaces2 - word.chars().count() + 1)));
-                    }
-                    (true, false) => {
-                        let top_n = orig_words_by_word.iter().take(length).collect::<Vec<_>>();
-                        println!("{:?}",top_n);
-                    }
-                    (true, true) => {
-                        let top_n = orig_words_by_word.iter().take(length).rev().collect::<Vec<_>>();
-                        println!("{:?}",top_n);
-                    }
-                }
-                (None, words_to_vecs, log)
-            })
-        },
-        Command {
-            command: "r",
-            usage: "r <original> <query>",
-            description: "Provide the rank of <query> out of all words when sorted according to similarity to <original>",
-            run: Box::new(|params: Vec<&str>, original_words: HashMap<&str, Vec<f32>>, words_to_vecs: HashMap<&str, Vec<f32>>, log: Vec<(String, f32)>, usage: &str, _: Vec<Command>,| {
-                let mut params = params.into_iter().skip(1);
-                let original;
-                let query;
-                let sim;
-                if let Some(x) = params.next() {
-                    original = x;
-                } else {
-                    println!("Usage: {usage}");
-                    return (None, words_to_vecs, log);
-                }
-                if let Some(y) = params.next() {
-                    query = y;
-                } else {
-                    println!("Usage: {usage}");
-                    return (None, words_to_vecs, log);
-                }
-                if let Some(z) = original_words.get(original) {
-                    sim = z;
-                } else {
-                    println!("Unknown word {original}");
-                    return (None, words_to_vecs, log);
-                }
-                let mut orig_words_by_word = original_words
-                    .iter()
-                    .filter(|(a,_)| **a != original)
-                    .map(|(a, b)| {
-                        (
-                            a,
-                            dot_product(b, sim),
-                        )
-                    }).collect::<Vec<_>>();
-                orig_words_by_word.sort_by(|(_,a),(_,b)| b.total_cmp(a));
-                if let Some(rank) = orig_words_by_word.iter().position(|(a,_)| **a == query) {
-                    println!("{}",rank+1);
-                } else {
-                    println!("Unknown word {query}");
-                }
-                (None, words_to_vecs, log)
-            })
-        },
-    ];
-    commands.sort_by(|a, b| a.command.cmp(b.command));
-    commands
+fn list_models(config: &Config) -> ExitCode {
+    if config.models.is_empty() {
+        match Config::path() {
+            Some(path) => println!("No models in {}", path.display()),
+            None => println!("No config file"),
+        }
+        return ExitCode::SUCCESS;
+    }
+    let width = config
+        .models
+        .keys()
+        .map(|name| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (name, path) in config.models.iter().sorted() {
+        let default = config.default_model.as_deref() == Some(name.as_str());
+        println!(
+            "{} {name}{} {}",
+            if default { "*" } else { " " },
+            " ".repeat(width - name.chars().count()),
+            path.display()
+        );
+    }
+    ExitCode::SUCCESS
 }
 
-fn start_game() {
-    println!("Loading...");
-    let color_end = "\x1B[37m";
-    let mut reader = BufReader::new(File::open("./words.bin").unwrap());
-
-    let embeddings: Embeddings<SimpleVocab, StorageViewWrap> =
-        Embeddings::read_embeddings(&mut reader).unwrap();
-    let mut words_to_vecs = HashMap::new();
-    for word in embeddings.vocab().words().iter() {
-        words_to_vecs.insert(word.as_str(), embeddings.embedding(word).unwrap().to_vec());
-    }
+fn load(path: &Path, format: Option<EmbeddingFormat>) -> EmbeddingStore {
+    eprintln!("Loading...");
+    let start = Instant::now();
+    let store = EmbeddingStore::load_format(path, format).unwrap_or_else(|e| fail(e));
+    eprintln!(
+        "Loaded {} words with {} dimensions in {:.2?}",
+        store.len(),
+        store.dims(),
+        start.elapsed()
+    );
+    store
+}
 
-    let mut rl: Editor<(), MemHistory> = Editor::with_history(
+fn editor() -> Editor<(), MemHistory> {
+    Editor::with_history(
         Builder::new().auto_add_history(true).build(),
         MemHistory::new(),
     )
-    .unwrap();
-
-    let words: Vec<_> = words_to_vecs.keys().collect();
-    let answer = words.get(thread_rng().gen_range(0..words.len())).unwrap();
-    let ans_embedding = words_to_vecs.get(**answer).unwrap();
-    let mut similarities = HashMap::new();
-    words_to_vecs.iter().for_each(|(a, b)| {
-        similarities.insert(a, (10000. * dot_product(ans_embedding, b)).round() / 100.);
-    });
-    let mut most_similar = similarities.iter().collect::<Vec<_>>();
-    most_similar.sort_by(|(_, b), (_, d)| d.total_cmp(b));
-    let mut similarities = HashMap::new();
-    most_similar
-        .iter()
-        .enumerate()
-        .for_each(|(index, (word, similarity))| {
-            similarities.insert(word, (similarity, index));
-        });
-    let mut guesses = 0;
-    let mut guessed = HashSet::new();
-    let mut log = Vec::new();
-    print!("\x1B[2J\x1B[1;1H");
-    let _ = io::stdout().flush();
-    let mut max_lens = (0, 0, 0, 0);
-    let mut most_recent = 0;
-    let screen_height;
-    let mut best_guessed = 0;
-    if let Some((_, height)) = term_size::dimensions() {
-        screen_height = height - 6;
-    } else {
-        screen_height = 34;
-    }
-    println!("Ready! Enter a word to start. Similarity ranges from -100 (worst) to 100 (best). Type !quit to exit, !hint for a hint, or !help for help.");
-    loop {
-        let line = rl.readline("semantle> ");
-        if line.is_err() {
-            exit(0);
-        }
-        let word = line.unwrap();
-        let mut word = word.trim().to_string();
+    .unwrap_or_else(|e| fail(e))
+}
 
-        if word == ***answer {
-            guesses += 1;
-            println!("You found it in {guesses}! The word is {answer}.");
-            exit(0);
-        }
+/// Where the solver reads its commands from.
+enum Input {
+    /// A terminal, with a prompt and line editing.
+    Terminal(Box<Editor<(), MemHistory>>),
+    /// A script file or piped stdin, read without prompts.
+    Script(Box<dyn BufRead>),
+}
 
-        match word.as_str() {
-            "!quit" => {
-                println!("Goodbye! The word was {answer}.");
-                exit(0);
-            }
-            "!help" => {
-                println!("Enter a word. You'll receive a number, which represents the semantic similarity between your word and the answer. -100 is the worst, 100 is the best. Type !quit to exit, !hint to get a hint, or !help to see this message again.");
-                continue;
-            }
-            "!hint" => {
-                word = most_similar
-                    .get(1000 - best_guessed - 1)
-                    .unwrap()
-                    .0
-                    .to_string();
+impl Input {
+    fn read_line(&mut self, prompt: &str) -> Option<String> {
+        match self {
+            Input::Terminal(rl) => rl.readline(prompt).ok(),
+            Input::Script(reader) => {
+                let mut line = String::new();
+                match reader.read_line(&mut line).unwrap_or_else(|e| fail(e)) {
+                    0 => None,
+                    _ => Some(line.trim_end_matches(['\n', '\r']).to_string()),
+                }
             }
-            _ => {}
         }
+    }
+}
 
-        if let Some(x) = similarities.get(&&&word.as_str()) {
-            if guessed.insert(word.clone()) {
-                max_lens.1 = max_lens.1.max(word.chars().count());
-                guesses += 1;
-                max_lens.0 = max_lens.0.max(guesses.to_string().len());
-                if **x.0 == 100. {}
-                max_lens.2 = max_lens.2.max(x.0.to_string().len());
-                log.push((guesses, word.clone(), x.0, x.1));
-                most_recent = guesses;
-            } else {
-                most_recent = log.iter().find(|i| i.1 == word).unwrap().0;
-            }
-            print!("\x1B[2J\x1B[2;1H");
-        } else {
+fn fail(error: impl Display) -> ! {
+    eprintln!("Error: {error}");
+    exit(1);
+}
+
+fn start_solver(
+    store: &EmbeddingStore,
+    settings: Settings,
+    session: Option<&Path>,
+    mut input: Input,
+) -> ExitCode {
+    // Scripts run without screen clears or banners, and stop at the first
+    // error with a failing exit code. JSON output gets neither either, so
+    // stdout holds nothing but JSON.
+    let scripted = matches!(input, Input::Script(_));
+    let plain = !scripted && !settings.json;
+    let clear = settings.clear && plain;
+    let mut solver = Solver::new(store, settings);
+    if let Some(session) = session.filter(|session| session.exists()) {
+        let saved_model = solver
+            .state_mut()
+            .resume(session)
+            .unwrap_or_else(|e| fail(e));
+        if let Some(model) = saved_model {
+            eprintln!(
+                "Warning: {} was saved with {}",
+                session.display(),
+                model.display()
+            );
+        }
+        eprintln!(
+            "Resumed {} words from {}",
+            solver.state().log().len(),
+            session.display()
+        );
+    }
+    if clear {
+        print!("\x1B[2J\x1B[1;1H");
+    }
+    let _ = io::stdout().flush();
+    if plain {
+        println!("Ready! Type a valid command or type h for help.");
+    }
+    loop {
+        let line = input.read_line("semantle> ");
+        if clear {
             print!("\x1B[2J\x1B[1;1H");
-            println!("Unknown word {word}");
-            if most_recent == 0 {
-                continue;
-            }
         }
-        let mut temp_log = log
-            .iter()
-            .filter(|i| i.0 != most_recent)
-            .collect::<Vec<_>>();
-        temp_log.sort_by(|(_, _, a, _), (_, _, b, _)| (***a).total_cmp(b).reverse());
-        let (guess, word, sim, index) = log.get(most_recent - 1).unwrap();
-        let num_spaces_4;
-        let prefix;
-        match (sim, index) {
-            (_, 0..=999) => {
-                max_lens.3 = max_lens.3.max(5 + (1000 - index).to_string().len());
-                num_spaces_4 = max_lens.3 - (5 + (1000 - index).to_string().len());
-                prefix = "\x1B[37m";
+        let _ = io::stdout().flush();
+        let Some(line) = line else {
+            return ExitCode::SUCCESS;
+        };
+        if scripted && (line.trim().is_empty() || line.trim_start().starts_with('#')) {
+            continue;
+        }
+        let mut result = solver.execute(&line);
+        if let Ok(CommandOutput::Paste) = result {
+            if !scripted {
+                solver.print(&CommandOutput::Paste);
             }
-            (x, _) => {
-                if ***x >= 40. {
-                    max_lens.3 = max_lens.3.max(10);
-                    num_spaces_4 = max_lens.3 - 10;
-                    prefix = "\x1B[33m";
-                } else if ***x >= 30. {
-                    max_lens.3 = max_lens.3.max(8);
-                    num_spaces_4 = max_lens.3 - 8;
-                    prefix = "\x1B[31m";
-                } else if ***x >= 20. {
-                    max_lens.3 = max_lens.3.max(7);
-                    num_spaces_4 = max_lens.3 - 7;
-                    prefix = "\x1B[31m";
-                } else if ***x >= 0. {
-                    max_lens.3 = max_lens.3.max(6);
-                    num_spaces_4 = max_lens.3 - 6;
-                    prefix = "\x1B[36m";
-                } else {
-                    max_lens.3 = max_lens.3.max(8);
-                    num_spaces_4 = max_lens.3 - 8;
-                    prefix = "\x1B[34m";
+            let mut text = String::new();
+            while let Some(row) = input.read_line("") {
+                if row.trim().is_empty() {
+                    break;
                 }
+                text += &row;
+                text.push('\n');
             }
+            result = Ok(solver.import(&text));
         }
-        let width = max_lens.0 + max_lens.1 + max_lens.2 + max_lens.3 + 5;
-        println!("┌{}┐", "─".repeat(width));
-        print!("│ ");
-        print!("{prefix}{guess}");
-        let num_spaces_1 = max_lens.0 - guess.to_string().len() + 1;
-        print!("{}", " ".repeat(num_spaces_1));
-        print!("{word}");
-        let num_spaces_2 = max_lens.1 - word.chars().count() + 1;
-        print!("{}", " ".repeat(num_spaces_2));
-        print!("{sim}");
-        let num_spaces_3 = max_lens.2 - sim.to_string().len() + 1;
-        print!("{}", " ".repeat(num_spaces_3));
-        match (sim, index) {
-            (_, 0..=999) => {
-                print!("{}/1000", 1000 - index);
-                best_guessed = best_guessed.max(1000 - index);
-            }
-            (x, _) => {
-                if ***x >= 40. {
-                    print!("(scalding)");
-                } else if ***x >= 30. {
-                    print!("(toasty)");
-                } else if ***x >= 20. {
-                    print!("(tepid)");
-                } else if ***x >= 0. {
-                    print!("(cold)");
-                } else {
-                    print!("(frigid)");
+        if let Some(session) = session {
+            if let Err(e) = solver.state().save(session) {
+                if scripted {
+                    fail(e);
                 }
+                println!("{e}");
             }
         }
-        print!("{}{color_end} │", " ".repeat(num_spaces_4));
-
-        let columns = 1.max((temp_log.len() + screen_height - 1) / screen_height);
-        let temp_log_formatted = temp_log
-            .into_iter()
-            .map(|(guess, word, sim, index)| {
-                format_string(
-                    word,
-                    *guess,
-                    max_lens,
-                    ***sim,
-                    match (sim, index) {
-                        (_, 0..=999) => ("\x1B[37m", format!("{}/1000", 1000 - index)),
-                        (x, _) => {
-                            if ***x >= 40. {
-                                ("\x1B[33m", "(scalding)".to_string())
-                            } else if ***x >= 30. {
-                                ("\x1B[31m", "(toasty)".to_string())
-                            } else if ***x >= 20. {
-                                ("\x1B[31m", "(tepid)".to_string())
-                            } else if ***x >= 0. {
-                                ("\x1B[36m", "(cold)".to_string())
-                            } else {
-                                ("\x1B[34m", "(frigid)".to_string())
-                            }
-                        }
-                    },
-                )
-            })
-            .collect::<Vec<_>>();
-        for i in 0..columns {
-            let words = if i == columns - 1 {
-                &temp_log_formatted[screen_height * i..]
-            } else {
-                &temp_log_formatted[screen_height * i..screen_height * (i + 1)]
-            };
-            print_column(words, width, i, 3, i == columns - 1, screen_height);
+        match result {
+            Ok(CommandOutput::Quit(code)) => return ExitCode::from(code as u8),
+            Ok(output) => solver.print(&output),
+            Err(e) if scripted => {
+                if solver.json() {
+                    solver.print_error(&e);
+                    return ExitCode::FAILURE;
+                }
+                eprintln!("Error: {}: {e}", line.trim());
+                return ExitCode::FAILURE;
+            }
+            Err(e) => solver.print_error(&e),
         }
-        print!(
-            "\x1B[{};1H",
-            if temp_log_formatted.is_empty() { 5 } else { 6 }
-                + (screen_height.min(temp_log_formatted.len()))
-        );
-        let _ = io::stdout().flush();
     }
 }
 
-fn dot_product(v1: &[f32], v2: &[f32]) -> f32 {
-    v1.iter().zip(v2.iter()).map(|i| *i.0 * *i.1).sum()
-}
-
-fn filter_embeddings(v1: &[f32], v2: &[f32], target_val: f32) -> bool {
-    let res = dot_product(v1, v2) * 100.0;
-    res >= target_val - 0.005 && res < target_val + 0.005
-}
-
-fn update_words<'a>(
-    original_words: &HashMap<&'a str, Vec<f32>>,
-    words_to_vecs: &mut HashMap<&'a str, Vec<f32>>,
-    log: &[(String, f32)],
-) {
-    *words_to_vecs = original_words.clone();
-    for (word, val) in log.iter() {
-        let current_vec = original_words.get(word.as_str()).unwrap().clone();
-        let current_vec = current_vec.as_slice();
-        words_to_vecs.retain(|_, value| filter_embeddings(current_vec, value.as_slice(), *val));
-    }
-}
-
-enum AddWordState {
-    Normal,
-    Edit,
-    Remove,
-}
+fn start_game(store: &EmbeddingStore, settings: &Settings, rng: &mut impl Rng) -> ExitCode {
+    let mut game = Game::new(store, settings, rng);
+    let board = Board::new(settings, game.rank_threshold());
+    let mut rl = editor();
+    let mut most_recent = 0;
+    print!("\x1B[2J\x1B[1;1H");
+    let _ = io::stdout().flush();
+    println!("Ready! Enter a word to start. Similarity ranges from -100 (worst) to 100 (best). Type !quit to exit, !hint for a hint, or !help for help.");
+    loop {
+        let Ok(line) = rl.readline("semantle> ") else {
+            return ExitCode::SUCCESS;
+        };
+        let mut word = line.trim().to_string();
 
-fn print_column(
-    words: &[String],
-    width: usize,
-    column: usize,
-    lines_above: usize,
-    last_column: bool,
-    height: usize,
-) {
-    let mut lines_above = lines_above;
-    let offset = if column >= 1 { 1 } else { 0 };
-    if words.is_empty() && column == 0 {
-        lines_above -= 1;
-    } else {
-        print!(
-            "{}{}",
-            if column == 0 {
-                format!("\x1B[{};1H├{}", lines_above + 1, "─".repeat(width))
-            } else if column == 1 {
-                format!(
-                    "\x1B[{};{}H┼{}",
-                    lines_above + 1,
-                    column * (width + offset) + 1,
-                    "─".repeat(width)
-                )
-            } else {
-                format!(
-                    "\x1B[{};{}H┬{}",
-                    lines_above + 1,
-                    column * (width + offset) + 1,
-                    "─".repeat(width)
-                )
-            },
-            if last_column && column == 0 {
-                "┤"
-            } else if last_column {
-                "┐"
-            } else {
-                ""
+        match word.as_str() {
+            "!quit" => {
+                println!("Goodbye! The word was {}.", game.answer());
+                return ExitCode::SUCCESS;
             }
-        );
-        for (index, word) in words.iter().enumerate() {
-            print!(
-                "\x1B[{};{}H│ {} {}",
-                index + 2 + lines_above,
-                if column == 0 {
-                    1
-                } else {
-                    column * (width + offset) + 1
-                },
-                word,
-                if last_column { "│" } else { "" }
-            );
+            "!help" => {
+                println!("Enter a word. You'll receive a number, which represents the semantic similarity between your word and the answer. -100 is the worst, 100 is the best. Type !quit to exit, !hint to get a hint, or !help to see this message again.");
+                continue;
+            }
+            "!hint" => word = game.hint().to_string(),
+            _ => {}
         }
-        if column != 0 {
-            for i in words.len()..height {
-                print!(
-                    "\x1B[{};{}H│",
-                    i + 2 + lines_above,
-                    if column == 0 {
-                        1
-                    } else {
-                        column * (width + offset) + 1
-                    },
-                );
+
+        match game.guess(&word) {
+            GuessOutcome::Found(guesses) => {
+                println!("You found it in {guesses}! The word is {}.", game.answer());
+                return ExitCode::SUCCESS;
+            }
+            GuessOutcome::Known(number) => {
+                most_recent = number;
+                print!("\x1B[2J\x1B[2;1H");
+            }
+            GuessOutcome::Unknown => {
+                print!("\x1B[2J\x1B[1;1H");
+                println!("Unknown word {word}");
+                if most_recent == 0 {
+                    continue;
+                }
             }
-            print!(
-                "\x1B[{};{}H┘",
-                height + 2 + lines_above,
-                if column == 0 {
-                    1
-                } else {
-                    column * (width + offset) + 1
-                },
-            );
         }
+        board.draw(game.guesses(), most_recent);
     }
-    print!(
-        "{}{}",
-        if column == 0 {
-            format!(
-                "\x1B[{};1H└{}",
-                words.len() + lines_above + 2,
-                "─".repeat(width)
-            )
-        } else if words.len() != height {
-            format!(
-                "\x1B[{};{}H├{}",
-                words.len() + lines_above + 2,
-                column * (width + offset) + 1,
-                "─".repeat(width)
-            )
-        } else {
-            format!(
-                "\x1B[{};{}H┴{}",
-                words.len() + lines_above + 2,
-                column * (width + offset) + 1,
-                "─".repeat(width)
-            )
-        },
-        if last_column { "┘" } else { "" }
-    );
-    if last_column {
-        println!();
-    }
-}
-
-fn format_string(
-    s: &str,
-    index: usize,
-    widths: (usize, usize, usize, usize),
-    sim: f32,
-    prefix_ranking: (&str, String),
-) -> String {
-    format!(
-        "{}{}{}{}{}{}{}{}{}\x1B[37m",
-        prefix_ranking.0,
-        index,
-        " ".repeat(1 + widths.0 - index.to_string().len()),
-        s,
-        " ".repeat(1 + widths.1 - s.chars().count()),
-        sim,
-        " ".repeat(1 + widths.2 - sim.to_string().len()),
-        prefix_ranking.1,
-        " ".repeat(widths.3 - prefix_ranking.1.len())
-    )
 }
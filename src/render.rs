@@ -0,0 +1,241 @@
+use std::io;
+use std::io::Write;
+
+use crate::band::Band;
+use crate::game::Guess;
+use crate::settings::Settings;
+
+/// Draws the guess table for [`Game`](crate::Game), wrapping it into extra
+/// columns when it outgrows the terminal.
+pub struct Board {
+    screen_height: usize,
+    color: bool,
+    rank_threshold: usize,
+}
+
+impl Board {
+    /// A board sized to the terminal. Rows are colored by how close the guess
+    /// is when the settings ask for color. Ranks are shown out of
+    /// `rank_threshold`, which should be the game's
+    /// [`Game::rank_threshold`](crate::Game::rank_threshold).
+    pub fn new(settings: &Settings, rank_threshold: usize) -> Self {
+        let screen_height = if let Some((_, height)) = term_size::dimensions() {
+            height - 6
+        } else {
+            34
+        };
+        Board {
+            screen_height,
+            color: settings.color.enabled(),
+            rank_threshold,
+        }
+    }
+
+    /// Draws the most recent guess on top and every other guess below it,
+    /// sorted by similarity.
+    pub fn draw(&self, guesses: &[Guess], most_recent: usize) {
+        let screen_height = self.screen_height;
+        let max_lens = guesses.iter().fold((0, 0, 0, 0), |lens, guess| {
+            (
+                lens.0.max(guess.number.to_string().len()),
+                lens.1.max(guess.word.chars().count()),
+                lens.2.max(guess.similarity.to_string().len()),
+                lens.3.max(self.label(guess).1.len()),
+            )
+        });
+        let mut temp_log = guesses
+            .iter()
+            .filter(|i| i.number != most_recent)
+            .collect::<Vec<_>>();
+        temp_log.sort_by(|a, b| a.similarity.total_cmp(&b.similarity).reverse());
+        let recent = &guesses[most_recent - 1];
+        let width = max_lens.0 + max_lens.1 + max_lens.2 + max_lens.3 + 5;
+        println!("┌{}┐", "─".repeat(width));
+        print!(
+            "│ {} │",
+            format_string(
+                &recent.word,
+                recent.number,
+                max_lens,
+                recent.similarity,
+                self.label(recent),
+                self.color,
+            )
+        );
+
+        let columns = 1.max(temp_log.len().div_ceil(screen_height));
+        let temp_log_formatted = temp_log
+            .into_iter()
+            .map(|guess| {
+                format_string(
+                    &guess.word,
+                    guess.number,
+                    max_lens,
+                    guess.similarity,
+                    self.label(guess),
+                    self.color,
+                )
+            })
+            .collect::<Vec<_>>();
+        for i in 0..columns {
+            let words = if i == columns - 1 {
+                &temp_log_formatted[screen_height * i..]
+            } else {
+                &temp_log_formatted[screen_height * i..screen_height * (i + 1)]
+            };
+            print_column(words, width, i, 3, i == columns - 1, screen_height);
+        }
+        print!(
+            "\x1B[{};1H",
+            if temp_log_formatted.is_empty() { 5 } else { 6 }
+                + (screen_height.min(temp_log_formatted.len()))
+        );
+        let _ = io::stdout().flush();
+    }
+
+    fn label(&self, guess: &Guess) -> (&'static str, String) {
+        match guess.rank {
+            Some(rank) => ("\x1B[37m", format!("{rank}/{}", self.rank_threshold)),
+            None => {
+                let band = Band::of(guess.similarity);
+                (band.color(), format!("({band})"))
+            }
+        }
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        let settings = Settings::default();
+        Self::new(&settings, settings.rank_threshold)
+    }
+}
+
+fn print_column(
+    words: &[String],
+    width: usize,
+    column: usize,
+    lines_above: usize,
+    last_column: bool,
+    height: usize,
+) {
+    let mut lines_above = lines_above;
+    let offset = if column >= 1 { 1 } else { 0 };
+    if words.is_empty() && column == 0 {
+        lines_above -= 1;
+    } else {
+        print!(
+            "{}{}",
+            if column == 0 {
+                format!("\x1B[{};1H├{}", lines_above + 1, "─".repeat(width))
+            } else if column == 1 {
+                format!(
+                    "\x1B[{};{}H┼{}",
+                    lines_above + 1,
+                    column * (width + offset) + 1,
+                    "─".repeat(width)
+                )
+            } else {
+                format!(
+                    "\x1B[{};{}H┬{}",
+                    lines_above + 1,
+                    column * (width + offset) + 1,
+                    "─".repeat(width)
+                )
+            },
+            if last_column && column == 0 {
+                "┤"
+            } else if last_column {
+                "┐"
+            } else {
+                ""
+            }
+        );
+        for (index, word) in words.iter().enumerate() {
+            print!(
+                "\x1B[{};{}H│ {} {}",
+                index + 2 + lines_above,
+                if column == 0 {
+                    1
+                } else {
+                    column * (width + offset) + 1
+                },
+                word,
+                if last_column { "│" } else { "" }
+            );
+        }
+        if column != 0 {
+            for i in words.len()..height {
+                print!(
+                    "\x1B[{};{}H│",
+                    i + 2 + lines_above,
+                    if column == 0 {
+                        1
+                    } else {
+                        column * (width + offset) + 1
+                    },
+                );
+            }
+            print!(
+                "\x1B[{};{}H┘",
+                height + 2 + lines_above,
+                if column == 0 {
+                    1
+                } else {
+                    column * (width + offset) + 1
+                },
+            );
+        }
+    }
+    print!(
+        "{}{}",
+        if column == 0 {
+            format!(
+                "\x1B[{};1H└{}",
+                words.len() + lines_above + 2,
+                "─".repeat(width)
+            )
+        } else if words.len() != height {
+            format!(
+                "\x1B[{};{}H├{}",
+                words.len() + lines_above + 2,
+                column * (width + offset) + 1,
+                "─".repeat(width)
+            )
+        } else {
+            format!(
+                "\x1B[{};{}H┴{}",
+                words.len() + lines_above + 2,
+                column * (width + offset) + 1,
+                "─".repeat(width)
+            )
+        },
+        if last_column { "┘" } else { "" }
+    );
+    if last_column {
+        println!();
+    }
+}
+
+fn format_string(
+    s: &str,
+    index: usize,
+    widths: (usize, usize, usize, usize),
+    sim: f32,
+    prefix_ranking: (&str, String),
+    color: bool,
+) -> String {
+    format!(
+        "{}{}{}{}{}{}{}{}{}{}",
+        if color { prefix_ranking.0 } else { "" },
+        index,
+        " ".repeat(1 + widths.0 - index.to_string().len()),
+        s,
+        " ".repeat(1 + widths.1 - s.chars().count()),
+        sim,
+        " ".repeat(1 + widths.2 - sim.to_string().len()),
+        prefix_ranking.1,
+        " ".repeat(widths.3 - prefix_ranking.1.len()),
+        if color { "\x1B[37m" } else { "" }
+    )
+}
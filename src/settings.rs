@@ -0,0 +1,150 @@
+use std::fmt;
+use std::io;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use serde::de;
+use serde::Deserialize;
+use serde::Deserializer;
+
+use crate::format::EmbeddingFormat;
+
+/// Options shared by the solver and the game. They are read from the
+/// `[defaults]` table of the config file and then overridden by command-line
+/// flags.
+///
+/// ```toml
+/// [defaults]
+/// embeddings = "/data/GoogleNews-vectors-negative300.bin"
+/// clear = true
+/// color = "never"
+/// tolerance = 0.005
+/// rank_threshold = 1000
+/// threads = 8
+/// json = false
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// Embeddings file to load when no path or model is given.
+    pub embeddings: Option<PathBuf>,
+    pub format: Option<EmbeddingFormat>,
+    /// Clear the screen after every solver command.
+    pub clear: bool,
+    pub color: ColorChoice,
+    /// How far, in similarity points, a word's similarity may be from a
+    /// logged value and still match it. Never negative.
+    #[serde(deserialize_with = "deserialize_tolerance")]
+    pub tolerance: f32,
+    /// How many of the answer's nearest neighbors get a rank, the 1000 in
+    /// Semantle's "N/1000".
+    pub rank_threshold: usize,
+    /// Threads for the solver's scans; all cores if unset.
+    pub threads: Option<usize>,
+    /// Print the solver's output as JSON.
+    pub json: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            embeddings: None,
+            format: None,
+            clear: false,
+            color: ColorChoice::Auto,
+            tolerance: 0.005,
+            rank_threshold: 1000,
+            threads: None,
+            json: false,
+        }
+    }
+}
+
+/// Parses a tolerance, which must be a number no less than zero.
+pub fn parse_tolerance(s: &str) -> Result<f32, String> {
+    let tolerance = s
+        .parse::<f32>()
+        .map_err(|_| format!("Invalid tolerance {s}, expected a number"))?;
+    check_tolerance(tolerance)
+}
+
+fn check_tolerance(tolerance: f32) -> Result<f32, String> {
+    if tolerance >= 0. {
+        Ok(tolerance)
+    } else {
+        Err(format!(
+            "Invalid tolerance {tolerance}, it can't be negative"
+        ))
+    }
+}
+
+fn deserialize_tolerance<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    check_tolerance(f32::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Color when standard output is a terminal.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Auto => io::stdout().is_terminal(),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!(
+                "Unknown color choice {s}, expected auto, always or never"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ColorChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ColorChoice::Auto => "auto",
+            ColorChoice::Always => "always",
+            ColorChoice::Never => "never",
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for ColorChoice {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tolerances_cannot_be_negative() {
+        assert_eq!(parse_tolerance("0.01"), Ok(0.01));
+        assert!(parse_tolerance("-0.01").is_err());
+        assert!(parse_tolerance("NaN").is_err());
+        assert!(toml::from_str::<Settings>("tolerance = 0").is_ok());
+        assert!(toml::from_str::<Settings>("tolerance = -1").is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use ndarray::Array1;
+use ndarray::Array2;
+use ndarray::ArrayView1;
+use ndarray::Axis;
+use ndarray::Zip;
+use rayon::prelude::*;
+
+use crate::embeddings::EmbeddingStore;
+
+/// Similarity queries answered with one matrix-vector product over the whole
+/// embedding matrix instead of a dot product per word.
+impl EmbeddingStore {
+    /// The similarity of every word to `query`, indexed like the store. The
+    /// rows are split across the rayon thread pool.
+    pub fn similarities(&self, query: ArrayView1<f32>) -> Array1<f32> {
+        let matrix = self.matrix();
+        let mut sims = Array1::zeros(matrix.nrows());
+        Zip::from(&mut sims)
+            .and(matrix.rows())
+            .par_for_each(|sim, row| *sim = row.dot(&query));
+        sims
+    }
+
+    /// The similarity of every word to the word at `index`.
+    pub fn similarities_to(&self, index: u32) -> Array1<f32> {
+        self.similarities(self.matrix().row(index as usize))
+    }
+
+    /// The `n` words closest to the word at `index`, nearest first, leaving
+    /// out the word itself.
+    pub fn neighbors(&self, index: u32, n: usize) -> Vec<(u32, f32)> {
+        let sims = self.similarities_to(index);
+        let neighbors = self
+            .indices()
+            .filter(|&i| i != index)
+            .map(|i| (i, sims[i as usize]))
+            .collect();
+        top(neighbors, n)
+    }
+
+    /// The 1-based position of `query` among the neighbors of `original`.
+    /// Ties are broken by vocabulary order.
+    pub fn rank(&self, original: u32, query: u32) -> usize {
+        let sims = self.similarities_to(original);
+        rank_in(&sims, original, query)
+    }
+
+    /// Copies the rows for `indices` into a matrix of their own, so that
+    /// repeated queries against a subset only touch those rows.
+    pub fn rows(&self, indices: &[u32]) -> Array2<f32> {
+        let indices = indices.iter().map(|&i| i as usize).collect::<Vec<_>>();
+        self.matrix().select(Axis(0), &indices)
+    }
+}
+
+/// The 1-based position of `query` when every word but `original` is sorted by
+/// `sims`, highest first.
+pub fn rank_in(sims: &Array1<f32>, original: u32, query: u32) -> usize {
+    let target = sims[query as usize];
+    let ahead = sims
+        .as_slice()
+        .expect("similarities are contiguous")
+        .par_iter()
+        .enumerate()
+        .filter(|&(i, &s)| {
+            i != original as usize
+                && i != query as usize
+                && (s > target || (s == target && i < query as usize))
+        })
+        .count();
+    ahead + 1
+}
+
+/// The `n` highest scoring words, highest first. Ties are broken by
+/// vocabulary order.
+pub(crate) fn top(mut scores: Vec<(u32, f32)>, n: usize) -> Vec<(u32, f32)> {
+    let by_score = |a: &(u32, f32), b: &(u32, f32)| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0));
+    if n < scores.len() {
+        scores.select_nth_unstable_by(n, by_score);
+        scores.truncate(n);
+    }
+    scores.sort_by(by_score);
+    scores
+}
@@ -0,0 +1,321 @@
+use std::collections::HashSet;
+
+use super::CommandError;
+use super::CommandOutput;
+use super::Recommendation;
+use super::SolverCommand;
+use crate::similarity::top;
+use crate::solver::geometric::estimate;
+use crate::solver::posterior::posterior;
+use crate::solver::recommend::recommend;
+use crate::solver::recommend::Metric;
+use crate::solver::recommend::GUESS_LIMIT;
+use crate::solver::Mode;
+use crate::solver::SolverState;
+
+pub struct Possible;
+
+impl SolverCommand for Possible {
+    fn name(&self) -> &'static str {
+        "p"
+    }
+
+    fn usage(&self) -> &'static str {
+        "p [-d|-e] [count]"
+    }
+
+    fn description(&self) -> &'static str {
+        "View remaining possible words, or in noisy and geometric mode the [count] likeliest with their scores"
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        let usage = CommandError::Usage(self.usage());
+        let mut debug = false;
+        let mut show_embeddings = false;
+        let mut count = None;
+        for term in args {
+            match *term {
+                "-d" => debug = true,
+                "-e" => {
+                    debug = true;
+                    show_embeddings = true;
+                }
+                x if count.is_none() => {
+                    count = Some(x.parse::<usize>().map_err(|_| usage.clone())?)
+                }
+                _ => return Err(usage),
+            }
+        }
+        let store = state.store;
+        match state.mode {
+            Mode::Strict => {}
+            Mode::Noisy { sigma } => {
+                let words = posterior(store, &state.log, sigma, count.unwrap_or(20))
+                    .into_iter()
+                    .map(|(index, probability)| (store.word(index).to_string(), probability))
+                    .collect();
+                return Ok(CommandOutput::Posterior { words, debug });
+            }
+            Mode::Geometric => {
+                let estimate = estimate(store, &state.log).ok_or(CommandError::NoEstimate)?;
+                let sims = store.similarities(estimate.view());
+                // Logged words were not the answer, yet they fit the
+                // estimate best.
+                let logged = state
+                    .log
+                    .iter()
+                    .filter_map(|constraint| store.index(&constraint.word))
+                    .collect::<HashSet<_>>();
+                let scores = store
+                    .indices()
+                    .filter(|index| !logged.contains(index))
+                    .map(|index| (index, sims[index as usize]));
+                let words = top(scores.collect(), count.unwrap_or(20))
+                    .into_iter()
+                    .map(|(index, sim)| (store.word(index).to_string(), sim))
+                    .collect();
+                return Ok(CommandOutput::Estimate { words, debug });
+            }
+        }
+        if count.is_some() {
+            return Err(usage);
+        }
+        let words = state.candidates().map(str::to_string).collect();
+        let embeddings = show_embeddings.then(|| {
+            state
+                .candidates
+                .iter()
+                .map(|&index| store.vector(index).to_vec())
+                .collect()
+        });
+        Ok(CommandOutput::Candidates {
+            words,
+            embeddings,
+            ranks_deferred: state.ranks_deferred(),
+            debug,
+        })
+    }
+}
+
+pub struct Best;
+
+impl SolverCommand for Best {
+    fn name(&self) -> &'static str {
+        "fb"
+    }
+
+    fn usage(&self) -> &'static str {
+        "fb [count] [-i|-w] [-a [limit]]"
+    }
+
+    fn description(&self) -> &'static str {
+        "List the [count] best guesses by the expected number of words they leave, by information with -i, or by the worst case with -w. With -a, search the first [limit] words of the whole vocabulary, by the worst case unless told otherwise"
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        let usage = CommandError::Usage(self.usage());
+        let mut count = None;
+        let mut metric = None;
+        let mut vocabulary = false;
+        let mut limit: Option<usize> = None;
+        let mut terms = args.iter().peekable();
+        while let Some(term) = terms.next() {
+            match *term {
+                "-i" => metric = Some(Metric::Entropy),
+                "-w" => metric = Some(Metric::Worst),
+                "-a" => {
+                    vocabulary = true;
+                    limit = terms
+                        .next_if(|x| x.parse::<usize>().is_ok())
+                        .map(|x| x.parse().unwrap());
+                }
+                x if count.is_none() => {
+                    count = Some(x.parse::<usize>().map_err(|_| usage.clone())?)
+                }
+                _ => return Err(usage),
+            }
+        }
+        let metric = metric.unwrap_or(if vocabulary {
+            Metric::Worst
+        } else {
+            Metric::Expected
+        });
+        if state.candidates.is_empty() {
+            return Err(CommandError::NoCandidates);
+        }
+        let store = state.store;
+        let candidates = &state.candidates;
+        let all = store.indices().collect::<Vec<_>>();
+        // In geometric mode the candidates closest to the estimated answer
+        // are tried rather than the most frequent ones.
+        let closest = match state.mode {
+            Mode::Geometric if !vocabulary => estimate(store, &state.log).map(|estimate| {
+                let sims = store.similarities(estimate.view());
+                let scores = candidates
+                    .iter()
+                    .map(|&index| (index, sims[index as usize]));
+                top(scores.collect(), GUESS_LIMIT)
+                    .into_iter()
+                    .map(|(index, _)| index)
+                    .collect::<Vec<_>>()
+            }),
+            _ => None,
+        };
+        let guesses = match (vocabulary, limit, &closest) {
+            (true, Some(limit), _) => &all[..limit.min(all.len())],
+            (true, None, _) => &all[..],
+            (false, _, Some(closest)) => &closest[..],
+            (false, _, None) => &candidates[..GUESS_LIMIT.min(candidates.len())],
+        };
+        // With nothing logged the answer could be any word, so the opening
+        // guesses only depend on the model and are worked out once.
+        let scores = if state.log.is_empty() {
+            state
+                .openings
+                .entry((metric, guesses.len()))
+                .or_insert_with(|| recommend(store, guesses, candidates, metric))
+                .clone()
+        } else {
+            recommend(store, guesses, candidates, metric)
+        };
+        Ok(CommandOutput::Recommendations {
+            words: scores
+                .into_iter()
+                .take(count.unwrap_or(5))
+                .map(|(index, score)| Recommendation {
+                    word: store.word(index).to_string(),
+                    score,
+                    candidate: candidates.binary_search(&index).is_ok(),
+                })
+                .collect(),
+            metric,
+        })
+    }
+}
+
+pub struct Why;
+
+impl SolverCommand for Why {
+    fn name(&self) -> &'static str {
+        "why"
+    }
+
+    fn usage(&self) -> &'static str {
+        "why"
+    }
+
+    fn description(&self) -> &'static str {
+        "Find the fewest logged words that contradict each other, and count the words left with each one dropped"
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        if !args.is_empty() {
+            return Err(CommandError::Usage(self.usage()));
+        }
+        let conflict = state
+            .conflict()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| state.log[entry].clone())
+            .collect();
+        let dropped = state
+            .log
+            .clone()
+            .into_iter()
+            .zip(state.without_each())
+            .collect();
+        Ok(CommandOutput::Diagnosis {
+            remaining: state.candidates.len(),
+            conflict,
+            dropped,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::commands::Word;
+    use crate::solver::tests::settings;
+    use crate::solver::tests::store;
+
+    #[test]
+    fn recommends_guesses_best_first() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        Word.run(&["w1", ">0"], &mut state).unwrap();
+        for (args, metric) in [
+            (&["5"][..], Metric::Expected),
+            (&["5", "-i"], Metric::Entropy),
+            (&["5", "-w"], Metric::Worst),
+            (&["5", "-a"], Metric::Worst),
+        ] {
+            let Ok(CommandOutput::Recommendations {
+                words,
+                metric: used,
+            }) = Best.run(args, &mut state)
+            else {
+                panic!("fb {args:?} didn't recommend");
+            };
+            assert_eq!(used, metric);
+            assert_eq!(words.len(), 5);
+            let scores = words.iter().map(|r| r.score).collect::<Vec<_>>();
+            assert!(scores.windows(2).all(|pair| match metric {
+                Metric::Entropy => pair[0] >= pair[1],
+                _ => pair[0] <= pair[1],
+            }));
+        }
+    }
+
+    #[test]
+    fn recommends_nothing_without_candidates() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        Word.run(&["w1", ">100"], &mut state).unwrap();
+        assert_eq!(Best.run(&[], &mut state), Err(CommandError::NoCandidates));
+        assert_eq!(
+            Best.run(&["x"], &mut state),
+            Err(CommandError::Usage(Best.usage()))
+        );
+    }
+
+    #[test]
+    fn why_diagnoses_only_an_empty_set() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        Word.run(&["w1", ">0"], &mut state).unwrap();
+        let Ok(CommandOutput::Diagnosis {
+            remaining,
+            conflict,
+            dropped,
+        }) = Why.run(&[], &mut state)
+        else {
+            panic!("why didn't diagnose");
+        };
+        assert_eq!(remaining, state.candidates.len());
+        assert!(conflict.is_empty());
+        assert_eq!(dropped, [(state.log[0].clone(), store.len())]);
+
+        Word.run(&["w2", ">100"], &mut state).unwrap();
+        let Ok(CommandOutput::Diagnosis { conflict, .. }) = Why.run(&[], &mut state) else {
+            panic!("why didn't diagnose");
+        };
+        assert_eq!(conflict, [state.log[1].clone()]);
+    }
+
+    #[test]
+    fn the_estimate_never_ranks_logged_words_first() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        state.mode = Mode::Geometric;
+        assert_eq!(Possible.run(&[], &mut state), Err(CommandError::NoEstimate));
+        for (word, similarity) in [("w2", "10"), ("w3", "35")] {
+            Word.run(&[word, similarity], &mut state).unwrap();
+            let Ok(CommandOutput::Estimate { words, .. }) = Possible.run(&["3"], &mut state) else {
+                panic!("p didn't estimate");
+            };
+            assert_eq!(words.len(), 3);
+            assert!(words.iter().all(|(word, _)| word != "w2" && word != "w3"));
+        }
+    }
+}
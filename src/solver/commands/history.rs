@@ -0,0 +1,194 @@
+use super::CommandError;
+use super::CommandOutput;
+use super::SolverCommand;
+use crate::solver::SolverState;
+
+pub struct Undo;
+
+impl SolverCommand for Undo {
+    fn name(&self) -> &'static str {
+        "undo"
+    }
+
+    fn usage(&self) -> &'static str {
+        "undo"
+    }
+
+    fn description(&self) -> &'static str {
+        "Take back the last change to the logged words or the tolerance"
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        if !args.is_empty() {
+            return Err(CommandError::Usage(self.usage()));
+        }
+        let current = state.snapshot();
+        let previous = state
+            .history
+            .undo(current)
+            .ok_or(CommandError::NothingToUndo)?;
+        state.restore(previous);
+        Ok(CommandOutput::None)
+    }
+}
+
+pub struct Redo;
+
+impl SolverCommand for Redo {
+    fn name(&self) -> &'static str {
+        "redo"
+    }
+
+    fn usage(&self) -> &'static str {
+        "redo"
+    }
+
+    fn description(&self) -> &'static str {
+        "Make the last change that was undone again"
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        if !args.is_empty() {
+            return Err(CommandError::Usage(self.usage()));
+        }
+        let current = state.snapshot();
+        let next = state
+            .history
+            .redo(current)
+            .ok_or(CommandError::NothingToRedo)?;
+        state.restore(next);
+        Ok(CommandOutput::None)
+    }
+}
+
+pub struct Checkpoint;
+
+impl SolverCommand for Checkpoint {
+    fn name(&self) -> &'static str {
+        "checkpoint"
+    }
+
+    fn usage(&self) -> &'static str {
+        "checkpoint [name]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Save the logged words under <name> to restore later, or list the saved names"
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        match args {
+            [] => {
+                let mut names = state
+                    .history
+                    .checkpoints
+                    .keys()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                names.sort();
+                Ok(CommandOutput::Checkpoints(names))
+            }
+            [name] => {
+                let snapshot = state.snapshot();
+                state.history.checkpoints.insert(name.to_string(), snapshot);
+                Ok(CommandOutput::None)
+            }
+            _ => Err(CommandError::Usage(self.usage())),
+        }
+    }
+}
+
+pub struct Restore;
+
+impl SolverCommand for Restore {
+    fn name(&self) -> &'static str {
+        "restore"
+    }
+
+    fn usage(&self) -> &'static str {
+        "restore <name>"
+    }
+
+    fn description(&self) -> &'static str {
+        "Go back to the logged words saved under <name>"
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        let [name] = args else {
+            return Err(CommandError::Usage(self.usage()));
+        };
+        let snapshot = state
+            .history
+            .checkpoints
+            .get(*name)
+            .cloned()
+            .ok_or_else(|| CommandError::UnknownCheckpoint(name.to_string()))?;
+        state.record();
+        state.restore(snapshot);
+        Ok(CommandOutput::None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::commands::Word;
+    use crate::solver::tests::settings;
+    use crate::solver::tests::store;
+
+    #[test]
+    fn undoes_and_redoes() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        assert_eq!(Undo.run(&[], &mut state), Err(CommandError::NothingToUndo));
+        Word.run(&["w1", ">0"], &mut state).unwrap();
+        let candidates = state.candidates.clone();
+
+        Undo.run(&[], &mut state).unwrap();
+        assert!(state.log.is_empty());
+        assert_eq!(state.candidates.len(), store.len());
+        Redo.run(&[], &mut state).unwrap();
+        assert_eq!(state.log.len(), 1);
+        assert_eq!(state.candidates, candidates);
+        assert_eq!(Redo.run(&[], &mut state), Err(CommandError::NothingToRedo));
+        assert_eq!(
+            Undo.run(&["x"], &mut state),
+            Err(CommandError::Usage(Undo.usage()))
+        );
+    }
+
+    #[test]
+    fn a_new_change_forgets_what_was_undone() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        Word.run(&["w1", ">0"], &mut state).unwrap();
+        Undo.run(&[], &mut state).unwrap();
+        Word.run(&["w2", ">0"], &mut state).unwrap();
+        assert_eq!(Redo.run(&[], &mut state), Err(CommandError::NothingToRedo));
+    }
+
+    #[test]
+    fn restores_checkpoints() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        Word.run(&["w1", ">0"], &mut state).unwrap();
+        Checkpoint.run(&["one"], &mut state).unwrap();
+        let candidates = state.candidates.clone();
+        Word.run(&["w2", ">0"], &mut state).unwrap();
+        assert_eq!(
+            Checkpoint.run(&[], &mut state),
+            Ok(CommandOutput::Checkpoints(vec!["one".to_string()]))
+        );
+
+        Restore.run(&["one"], &mut state).unwrap();
+        assert_eq!(state.log.len(), 1);
+        assert_eq!(state.candidates, candidates);
+        assert_eq!(
+            Restore.run(&["two"], &mut state),
+            Err(CommandError::UnknownCheckpoint("two".to_string()))
+        );
+        // Restoring can itself be undone.
+        Undo.run(&[], &mut state).unwrap();
+        assert_eq!(state.log.len(), 2);
+    }
+}
@@ -0,0 +1,291 @@
+use super::CommandError;
+use super::CommandOutput;
+use super::SolverCommand;
+use crate::solver::Constraint;
+use crate::solver::Rank;
+use crate::solver::SolverState;
+
+enum AddWordState {
+    Normal,
+    Edit,
+    Remove,
+}
+
+pub struct Word;
+
+impl SolverCommand for Word {
+    fn name(&self) -> &'static str {
+        "w"
+    }
+
+    fn usage(&self) -> &'static str {
+        "w <word> <value|band|>value|low..high [~tolerance] [rN|r-]|-r|value -e>"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add a word with its similarity, edit an existing word's similarity, or remove a word"
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        let usage = CommandError::Usage(self.usage());
+        let mut mode = AddWordState::Normal;
+        let mut word = None;
+        let mut val = None;
+        let mut tolerance = None;
+        let mut rank = None;
+        for term in args {
+            match *term {
+                "-n" => mode = AddWordState::Normal,
+                "-e" => mode = AddWordState::Edit,
+                "-r" => mode = AddWordState::Remove,
+                x if word.is_none() => {
+                    if !state.store.contains(x) {
+                        return Err(CommandError::UnknownWord(x.to_string()));
+                    }
+                    word = Some(x.to_string());
+                }
+                x if x.starts_with('~') && tolerance.is_none() => {
+                    tolerance = Some(
+                        x[1..]
+                            .parse::<f32>()
+                            .ok()
+                            .filter(|tolerance| *tolerance >= 0.)
+                            .ok_or(usage.clone())?,
+                    )
+                }
+                x if x.starts_with('r') && rank.is_none() => {
+                    rank = Some(Rank::parse(x).ok_or(usage.clone())?)
+                }
+                x if val.is_none() => val = Some(x),
+                _ => return Err(usage),
+            }
+        }
+        let word = word.ok_or(usage.clone())?;
+        match mode {
+            AddWordState::Normal => {
+                let mut constraint =
+                    Constraint::parse(&word, val.ok_or(usage.clone())?, tolerance).ok_or(usage)?;
+                constraint.rank = rank;
+                if state.log.iter().any(|c| c.word == word) {
+                    return Err(CommandError::AlreadyLogged);
+                }
+                state.record();
+                state.push(constraint);
+            }
+            AddWordState::Edit => {
+                let position = state
+                    .log
+                    .iter()
+                    .position(|c| c.word == word)
+                    .ok_or(usage.clone())?;
+                if val.is_none() && tolerance.is_none() && rank.is_none() {
+                    return Err(usage);
+                }
+                let mut entry = state.log[position].clone();
+                let tolerance = tolerance.or(entry.tolerance);
+                let rank = rank.or(entry.rank);
+                if let Some(val) = val {
+                    entry = Constraint::parse(&word, val, tolerance).ok_or(usage)?;
+                }
+                entry.tolerance = tolerance;
+                entry.rank = rank;
+                state.record();
+                state.replace(position, entry);
+            }
+            AddWordState::Remove => {
+                let position = state.log.iter().position(|c| c.word == word).ok_or(usage)?;
+                state.record();
+                state.remove(position);
+            }
+        }
+        Ok(CommandOutput::None)
+    }
+}
+
+pub struct List;
+
+impl SolverCommand for List {
+    fn name(&self) -> &'static str {
+        "l"
+    }
+
+    fn usage(&self) -> &'static str {
+        "l [-d]"
+    }
+
+    fn description(&self) -> &'static str {
+        "List the guessed words with their similarities in human-readable or debug mode"
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        let debug = match args {
+            [] => false,
+            ["-d"] => true,
+            _ => return Err(CommandError::Usage(self.usage())),
+        };
+        Ok(CommandOutput::Log {
+            entries: state.log.clone(),
+            debug,
+        })
+    }
+}
+
+pub struct Tolerance;
+
+impl SolverCommand for Tolerance {
+    fn name(&self) -> &'static str {
+        "t"
+    }
+
+    fn usage(&self) -> &'static str {
+        "t [tolerance]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show or change how far a similarity may be from a logged value and still match it"
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        match args {
+            [] => {}
+            [tolerance] => {
+                let tolerance = tolerance
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|tolerance| *tolerance >= 0.)
+                    .ok_or(CommandError::Usage(self.usage()))?;
+                state.record();
+                state.set_tolerance(tolerance);
+            }
+            _ => return Err(CommandError::Usage(self.usage())),
+        }
+        Ok(CommandOutput::Tolerance(state.settings.tolerance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::settings;
+    use crate::solver::tests::store;
+    use crate::solver::Similarity;
+
+    #[test]
+    fn logs_a_word() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        assert_eq!(
+            Word.run(&["w1", "12.34"], &mut state),
+            Ok(CommandOutput::None)
+        );
+        assert_eq!(state.log.len(), 1);
+        assert_eq!(state.log[0].word, "w1");
+        assert_eq!(state.log[0].similarity, Similarity::Exact(12.34));
+        assert!(state.candidates.len() < store.len());
+    }
+
+    #[test]
+    fn rejects_bad_words() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        assert_eq!(
+            Word.run(&["nope", "12"], &mut state),
+            Err(CommandError::UnknownWord("nope".to_string()))
+        );
+        Word.run(&["w1", "12"], &mut state).unwrap();
+        assert_eq!(
+            Word.run(&["w1", "13"], &mut state),
+            Err(CommandError::AlreadyLogged)
+        );
+        assert_eq!(state.log.len(), 1);
+    }
+
+    #[test]
+    fn rejects_bad_usage() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        let usage = Err(CommandError::Usage(Word.usage()));
+        for args in [
+            &[][..],
+            &["w1"],
+            &["w1", "abc"],
+            &["w1", "12", "~-1"],
+            &["w1", "12", "13"],
+            &["w2", "-e"],
+        ] {
+            assert_eq!(Word.run(args, &mut state), usage, "{args:?}");
+        }
+        assert!(state.log.is_empty());
+        assert_eq!(state.candidates.len(), store.len());
+    }
+
+    #[test]
+    fn edits_and_removes_words() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        Word.run(&["w1", "12"], &mut state).unwrap();
+        Word.run(&["w1", "20", "-e"], &mut state).unwrap();
+        assert_eq!(state.log[0].similarity, Similarity::Exact(20.));
+        Word.run(&["w1", "-r"], &mut state).unwrap();
+        assert!(state.log.is_empty());
+        assert_eq!(state.candidates.len(), store.len());
+    }
+
+    #[test]
+    fn lists_the_log() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        Word.run(&["w1", "12"], &mut state).unwrap();
+        let Ok(CommandOutput::Log { entries, debug }) = List.run(&[], &mut state) else {
+            panic!("l didn't list the log");
+        };
+        assert_eq!(entries, state.log);
+        assert!(!debug);
+        assert_eq!(
+            List.run(&["-x"], &mut state),
+            Err(CommandError::Usage(List.usage()))
+        );
+    }
+
+    #[test]
+    fn shows_and_sets_the_tolerance() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        assert_eq!(
+            Tolerance.run(&[], &mut state),
+            Ok(CommandOutput::Tolerance(0.005))
+        );
+        assert_eq!(
+            Tolerance.run(&["0.1"], &mut state),
+            Ok(CommandOutput::Tolerance(0.1))
+        );
+        assert_eq!(state.settings.tolerance, 0.1);
+        let usage = Err(CommandError::Usage(Tolerance.usage()));
+        assert_eq!(Tolerance.run(&["-1"], &mut state), usage);
+        assert_eq!(Tolerance.run(&["x"], &mut state), usage);
+        assert_eq!(Tolerance.run(&["1", "2"], &mut state), usage);
+        assert_eq!(state.settings.tolerance, 0.1);
+    }
+
+    #[test]
+    fn logs_a_word_with_its_own_tolerance() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        Word.run(&["w1", "12", "~0.5"], &mut state).unwrap();
+        assert_eq!(state.log[0].tolerance, Some(0.5));
+    }
+
+    #[test]
+    fn logs_a_word_with_its_rank() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        Word.run(&["w1", "12", "r5"], &mut state).unwrap();
+        Word.run(&["w2", "-3", "r-"], &mut state).unwrap();
+        assert_eq!(state.log[0].rank, Some(Rank::At(5)));
+        assert_eq!(state.log[1].rank, Some(Rank::Outside));
+        assert_eq!(
+            Word.run(&["w3", "12", "r0"], &mut state),
+            Err(CommandError::Usage(Word.usage()))
+        );
+    }
+}
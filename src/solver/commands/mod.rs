@@ -0,0 +1,279 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde::Serializer;
+
+use super::Constraint;
+use super::Metric;
+use super::SolverState;
+
+mod candidates;
+mod history;
+mod log;
+mod neighbors;
+mod session;
+
+pub use candidates::Best;
+pub use candidates::Possible;
+pub use candidates::Why;
+pub use history::Checkpoint;
+pub use history::Redo;
+pub use history::Restore;
+pub use history::Undo;
+pub use log::List;
+pub use log::Tolerance;
+pub use log::Word;
+pub use neighbors::Closest;
+pub use neighbors::Rank;
+pub use session::Help;
+pub use session::Import;
+pub use session::Load;
+pub use session::Mode;
+pub use session::Quit;
+pub use session::Save;
+
+/// A command that can be typed at the solver prompt.
+pub trait SolverCommand {
+    /// The word that invokes the command.
+    fn name(&self) -> &'static str;
+    fn usage(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    /// Whether the command gets its arguments as typed instead of
+    /// lowercased, for commands that take file names.
+    fn case_sensitive(&self) -> bool {
+        false
+    }
+    /// Runs the command with the terms that followed its name.
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError>;
+}
+
+/// What a command produced, for the caller to display. In JSON it is an
+/// object with the variant's name as its only key, and scored words are
+/// objects naming their score.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandOutput {
+    None,
+    Quit(i32),
+    Help,
+    Log {
+        entries: Vec<Constraint>,
+        #[serde(skip)]
+        debug: bool,
+    },
+    Candidates {
+        words: Vec<String>,
+        /// Filled in when the vectors were asked for.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        embeddings: Option<Vec<Vec<f32>>>,
+        /// Whether rank feedback has not been checked yet, see
+        /// [`RANK_LIMIT`](crate::solver::RANK_LIMIT).
+        ranks_deferred: bool,
+        #[serde(skip)]
+        debug: bool,
+    },
+    /// The likeliest answers in noisy mode, likeliest first, with their
+    /// probabilities.
+    Posterior {
+        #[serde(serialize_with = "serialize_probabilities")]
+        words: Vec<(String, f32)>,
+        #[serde(skip)]
+        debug: bool,
+    },
+    /// The unlogged words closest to the estimated answer in geometric mode,
+    /// closest first, with the similarity the estimate predicts for them.
+    Estimate {
+        #[serde(serialize_with = "serialize_similarities")]
+        words: Vec<(String, f32)>,
+        #[serde(skip)]
+        debug: bool,
+    },
+    /// The words closest to a query, nearest first.
+    #[serde(serialize_with = "serialize_neighbors")]
+    Neighbors {
+        neighbors: Vec<(String, f32)>,
+        reverse: bool,
+        debug: bool,
+    },
+    Rank(usize),
+    Tolerance(f32),
+    Mode(super::Mode),
+    Saved(PathBuf),
+    /// A loaded session, with the embeddings file it was saved with if that
+    /// is not the loaded one.
+    Loaded {
+        path: PathBuf,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        saved_model: Option<PathBuf>,
+    },
+    /// The guesses logged by `import`, with the words the model doesn't know
+    /// and those that were already logged.
+    Imported {
+        added: Vec<String>,
+        unknown: Vec<String>,
+        skipped: Vec<String>,
+    },
+    /// The caller should read a pasted guess table up to a blank line and
+    /// pass it to [`Solver::import`](crate::Solver::import).
+    Paste,
+    /// The names saved with `checkpoint`, sorted.
+    Checkpoints(Vec<String>),
+    /// Why the candidates ran out: the fewest entries that contradict each
+    /// other, and how many words each entry rules out on top of the others.
+    Diagnosis {
+        remaining: usize,
+        conflict: Vec<Constraint>,
+        /// Each entry with the number of words left without it.
+        #[serde(serialize_with = "serialize_dropped")]
+        dropped: Vec<(Constraint, usize)>,
+    },
+    /// The best guesses, best first, with their scores.
+    Recommendations {
+        words: Vec<Recommendation>,
+        metric: Metric,
+    },
+}
+
+#[derive(Serialize)]
+struct WordSimilarity<'a> {
+    word: &'a str,
+    similarity: f32,
+}
+
+#[derive(Serialize)]
+struct WordProbability<'a> {
+    word: &'a str,
+    probability: f32,
+}
+
+#[derive(Serialize)]
+struct Dropped<'a> {
+    #[serde(flatten)]
+    entry: &'a Constraint,
+    left: usize,
+}
+
+fn serialize_similarities<S: Serializer>(
+    words: &[(String, f32)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(words.iter().map(|(word, similarity)| WordSimilarity {
+        word,
+        similarity: *similarity,
+    }))
+}
+
+fn serialize_probabilities<S: Serializer>(
+    words: &[(String, f32)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(words.iter().map(|(word, probability)| WordProbability {
+        word,
+        probability: *probability,
+    }))
+}
+
+/// Neighbors serialize as a bare list, in the order they are shown.
+fn serialize_neighbors<S: Serializer>(
+    neighbors: &[(String, f32)],
+    reverse: &bool,
+    _debug: &bool,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if *reverse {
+        let reversed = neighbors.iter().rev().cloned().collect::<Vec<_>>();
+        return serialize_similarities(&reversed, serializer);
+    }
+    serialize_similarities(neighbors, serializer)
+}
+
+fn serialize_dropped<S: Serializer>(
+    dropped: &[(Constraint, usize)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(
+        dropped
+            .iter()
+            .map(|(entry, left)| Dropped { entry, left: *left }),
+    )
+}
+
+/// A suggested guess and its score.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Recommendation {
+    pub word: String,
+    pub score: f32,
+    /// Whether the guess could itself be the answer.
+    pub candidate: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError {
+    UnknownCommand,
+    Usage(&'static str),
+    UnknownWord(String),
+    AlreadyLogged,
+    NothingToUndo,
+    NothingToRedo,
+    UnknownCheckpoint(String),
+    NoCandidates,
+    NoEstimate,
+    File { path: PathBuf, reason: String },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::UnknownCommand => write!(f, "Unknown command, please try again."),
+            CommandError::Usage(usage) => write!(f, "Usage: {usage}"),
+            CommandError::UnknownWord(word) => write!(f, "Unknown word {word}"),
+            CommandError::AlreadyLogged => write!(
+                f,
+                "This word already has a value. Try using -e to change an existing value."
+            ),
+            CommandError::NothingToUndo => write!(f, "There is nothing to undo."),
+            CommandError::NothingToRedo => write!(f, "There is nothing to redo."),
+            CommandError::UnknownCheckpoint(name) => write!(f, "No checkpoint named {name}"),
+            CommandError::File { path, reason } => {
+                write!(f, "Cannot use {}: {reason}", path.display())
+            }
+            CommandError::NoEstimate => write!(
+                f,
+                "Log a similarity value first. Ranks and one-sided ranges don't place the answer."
+            ),
+            CommandError::NoCandidates => write!(
+                f,
+                "No words fit the logged similarities. Try undo, or a wider tolerance."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// Every built-in command, sorted by name.
+pub fn all() -> Vec<Box<dyn SolverCommand>> {
+    let mut commands: Vec<Box<dyn SolverCommand>> = vec![
+        Box::new(Word),
+        Box::new(List),
+        Box::new(Possible),
+        Box::new(Quit),
+        Box::new(Help),
+        Box::new(Best),
+        Box::new(Closest),
+        Box::new(Rank),
+        Box::new(Tolerance),
+        Box::new(Undo),
+        Box::new(Redo),
+        Box::new(Checkpoint),
+        Box::new(Restore),
+        Box::new(Mode),
+        Box::new(Why),
+        Box::new(Save),
+        Box::new(Load),
+        Box::new(Import),
+    ];
+    commands.sort_by(|a, b| a.name().cmp(b.name()));
+    commands
+}
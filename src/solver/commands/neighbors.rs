@@ -0,0 +1,158 @@
+use super::CommandError;
+use super::CommandOutput;
+use super::SolverCommand;
+use crate::solver::SolverState;
+
+pub struct Closest;
+
+impl SolverCommand for Closest {
+    fn name(&self) -> &'static str {
+        "c"
+    }
+
+    fn usage(&self) -> &'static str {
+        "c <length> <word> [-dr]"
+    }
+
+    fn description(&self) -> &'static str {
+        "List the <length> closest words to <word>, optionally in debug mode and/or in reverse"
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        let usage = CommandError::Usage(self.usage());
+        let mut length = None;
+        let mut word = None;
+        let mut reverse = false;
+        let mut debug = false;
+        for term in args {
+            match (*term, length, word) {
+                (x, None, _) => length = Some(x.parse::<usize>().map_err(|_| usage.clone())?),
+                (x, _, None) => word = Some(x),
+                ("-d", _, _) => debug = !debug,
+                ("-r", _, _) => reverse = !reverse,
+                _ => return Err(usage),
+            }
+        }
+        let (Some(length), Some(word)) = (length, word) else {
+            return Err(usage);
+        };
+        let store = state.store;
+        let index = store
+            .index(word)
+            .ok_or_else(|| CommandError::UnknownWord(word.to_string()))?;
+        let neighbors = store
+            .neighbors(index, length)
+            .into_iter()
+            .map(|(i, sim)| (store.word(i).to_string(), sim))
+            .collect();
+        Ok(CommandOutput::Neighbors {
+            neighbors,
+            reverse,
+            debug,
+        })
+    }
+}
+
+pub struct Rank;
+
+impl SolverCommand for Rank {
+    fn name(&self) -> &'static str {
+        "r"
+    }
+
+    fn usage(&self) -> &'static str {
+        "r <original> <query>"
+    }
+
+    fn description(&self) -> &'static str {
+        "Provide the rank of <query> out of all words when sorted according to similarity to <original>"
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        let (original, query) = match args {
+            [original, query, ..] => (*original, *query),
+            _ => return Err(CommandError::Usage(self.usage())),
+        };
+        let store = state.store;
+        let original = store
+            .index(original)
+            .ok_or_else(|| CommandError::UnknownWord(original.to_string()))?;
+        match store.index(query) {
+            Some(query) if query != original => {
+                Ok(CommandOutput::Rank(store.rank(original, query)))
+            }
+            _ => Err(CommandError::UnknownWord(query.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::settings;
+    use crate::solver::tests::store;
+
+    #[test]
+    fn lists_the_closest_words() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        let Ok(CommandOutput::Neighbors {
+            neighbors,
+            reverse,
+            debug,
+        }) = Closest.run(&["3", "w0", "-r"], &mut state)
+        else {
+            panic!("c didn't list neighbors");
+        };
+        let expected = store
+            .neighbors(0, 3)
+            .into_iter()
+            .map(|(index, sim)| (store.word(index).to_string(), sim))
+            .collect::<Vec<_>>();
+        assert_eq!(neighbors, expected);
+        assert!(neighbors.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert!(reverse);
+        assert!(!debug);
+    }
+
+    #[test]
+    fn closest_rejects_bad_input() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        assert_eq!(
+            Closest.run(&["3", "nope"], &mut state),
+            Err(CommandError::UnknownWord("nope".to_string()))
+        );
+        let usage = Err(CommandError::Usage(Closest.usage()));
+        assert_eq!(Closest.run(&["w0"], &mut state), usage);
+        assert_eq!(Closest.run(&["3"], &mut state), usage);
+        assert_eq!(Closest.run(&["3", "w0", "-x"], &mut state), usage);
+    }
+
+    #[test]
+    fn ranks_a_word() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        let nearest = store.word(store.neighbors(0, 1)[0].0);
+        assert_eq!(
+            Rank.run(&["w0", nearest], &mut state),
+            Ok(CommandOutput::Rank(1))
+        );
+        assert_eq!(
+            Rank.run(&["w0", "w5"], &mut state),
+            Ok(CommandOutput::Rank(store.rank(0, 5)))
+        );
+        assert_eq!(
+            Rank.run(&["w0", "w0"], &mut state),
+            Err(CommandError::UnknownWord("w0".to_string()))
+        );
+        assert_eq!(
+            Rank.run(&["nope", "w0"], &mut state),
+            Err(CommandError::UnknownWord("nope".to_string()))
+        );
+        assert_eq!(
+            Rank.run(&["w0"], &mut state),
+            Err(CommandError::Usage(Rank.usage()))
+        );
+    }
+}
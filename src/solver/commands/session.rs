@@ -0,0 +1,184 @@
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use super::CommandError;
+use super::CommandOutput;
+use super::SolverCommand;
+use crate::solver;
+use crate::solver::SolverState;
+
+pub struct Quit;
+
+impl SolverCommand for Quit {
+    fn name(&self) -> &'static str {
+        "q"
+    }
+
+    fn usage(&self) -> &'static str {
+        "q"
+    }
+
+    fn description(&self) -> &'static str {
+        "Quit"
+    }
+
+    fn run(&self, args: &[&str], _state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        if !args.is_empty() {
+            return Err(CommandError::Usage(self.usage()));
+        }
+        Ok(CommandOutput::Quit(0))
+    }
+}
+
+pub struct Help;
+
+impl SolverCommand for Help {
+    fn name(&self) -> &'static str {
+        "h"
+    }
+
+    fn usage(&self) -> &'static str {
+        "h"
+    }
+
+    fn description(&self) -> &'static str {
+        "Display this help message"
+    }
+
+    fn run(&self, args: &[&str], _state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        if !args.is_empty() {
+            return Err(CommandError::Usage(self.usage()));
+        }
+        Ok(CommandOutput::Help)
+    }
+}
+
+pub struct Mode;
+
+impl SolverCommand for Mode {
+    fn name(&self) -> &'static str {
+        "mode"
+    }
+
+    fn usage(&self) -> &'static str {
+        "mode [strict|noisy [sigma]|geometric]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show or change whether words must fit every similarity, are ranked by likelihood allowing [sigma] points of noise, or by closeness to a least-squares estimate of the answer"
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        let usage = CommandError::Usage(self.usage());
+        match args {
+            [] => {}
+            ["strict"] => state.mode = solver::Mode::Strict,
+            ["geometric"] => state.mode = solver::Mode::Geometric,
+            ["noisy"] => state.mode = solver::Mode::Noisy { sigma: 1. },
+            ["noisy", sigma] => {
+                let sigma = sigma
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|sigma| *sigma > 0.)
+                    .ok_or(usage)?;
+                state.mode = solver::Mode::Noisy { sigma };
+            }
+            _ => return Err(usage),
+        }
+        Ok(CommandOutput::Mode(state.mode))
+    }
+}
+
+pub struct Save;
+
+impl SolverCommand for Save {
+    fn name(&self) -> &'static str {
+        "save"
+    }
+
+    fn usage(&self) -> &'static str {
+        "save <file>"
+    }
+
+    fn description(&self) -> &'static str {
+        "Save the logged words and settings to <file> to pick the puzzle up later"
+    }
+
+    fn case_sensitive(&self) -> bool {
+        true
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        let [path] = args else {
+            return Err(CommandError::Usage(self.usage()));
+        };
+        state.save(Path::new(path))?;
+        Ok(CommandOutput::Saved(PathBuf::from(path)))
+    }
+}
+
+pub struct Load;
+
+impl SolverCommand for Load {
+    fn name(&self) -> &'static str {
+        "load"
+    }
+
+    fn usage(&self) -> &'static str {
+        "load <file>"
+    }
+
+    fn description(&self) -> &'static str {
+        "Replace the logged words and settings with those saved in <file>"
+    }
+
+    fn case_sensitive(&self) -> bool {
+        true
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        let [path] = args else {
+            return Err(CommandError::Usage(self.usage()));
+        };
+        let saved_model = state.load(Path::new(path))?;
+        Ok(CommandOutput::Loaded {
+            path: PathBuf::from(path),
+            saved_model,
+        })
+    }
+}
+
+pub struct Import;
+
+impl SolverCommand for Import {
+    fn name(&self) -> &'static str {
+        "import"
+    }
+
+    fn usage(&self) -> &'static str {
+        "import [file]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Log the guesses in a table copied from the Semantle website, read from [file] or pasted up to a blank line"
+    }
+
+    fn case_sensitive(&self) -> bool {
+        true
+    }
+
+    fn run(&self, args: &[&str], state: &mut SolverState) -> Result<CommandOutput, CommandError> {
+        match args {
+            [] => Ok(CommandOutput::Paste),
+            [path] => {
+                let text = fs::read_to_string(path).map_err(|e| CommandError::File {
+                    path: PathBuf::from(path),
+                    reason: e.to_string(),
+                })?;
+                Ok(state.import(&text))
+            }
+            _ => Err(CommandError::Usage(self.usage())),
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use std::fmt;
+use std::ops::Bound;
+use std::ops::RangeBounds;
+
+use crate::band::Band;
+use crate::solver::filter_embeddings;
+
+/// A logged guess: the answer's similarity to `word`, as Semantle showed it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constraint {
+    pub word: String,
+    pub similarity: Similarity,
+    /// Tolerance given with `~`, which replaces the session's.
+    pub tolerance: Option<f32>,
+    pub rank: Option<Rank>,
+    /// Decimal places the similarity was typed with, so that `45.3` matches
+    /// anything that rounds to it.
+    decimals: usize,
+}
+
+impl Constraint {
+    /// Parses a similarity as typed, keeping track of how many decimals it
+    /// was given with. Besides a value this can be a band name such as
+    /// `tepid`, an inequality such as `>30` or `<=-5`, or a range such as
+    /// `20..30`.
+    pub fn parse(word: &str, similarity: &str, tolerance: Option<f32>) -> Option<Self> {
+        let decimals = similarity
+            .split_once('.')
+            .map_or(0, |(_, decimals)| decimals.len());
+        Some(Constraint {
+            word: word.to_string(),
+            similarity: Similarity::parse(similarity)?,
+            tolerance,
+            rank: None,
+            decimals,
+        })
+    }
+
+    /// The similarity as it was typed.
+    pub fn typed_similarity(&self) -> String {
+        match self.similarity {
+            Similarity::Exact(value) => format!("{value:.*}", self.decimals),
+            similarity => similarity.to_string(),
+        }
+    }
+
+    /// The tolerance to filter with: the constraint's own if it has one,
+    /// otherwise the session's, widened to the precision that was typed.
+    pub fn tolerance(&self, session: f32) -> f32 {
+        let precision = 0.5 / 10f32.powi(self.decimals as i32);
+        self.tolerance.unwrap_or(session.max(precision))
+    }
+
+    /// Whether a word at `similarity` (as a cosine) satisfies the constraint.
+    /// Ranges are checked against the similarity rounded to two decimals, as
+    /// Semantle picks the band from what it shows, and are only widened by a
+    /// tolerance given with `~`.
+    pub fn matches(&self, similarity: f32, session: f32) -> bool {
+        match self.similarity {
+            Similarity::Exact(value) => {
+                filter_embeddings(similarity, value, self.tolerance(session))
+            }
+            Similarity::Range(low, high) => {
+                let widen = self.tolerance.unwrap_or(0.);
+                let shown = (similarity * 10000.).round() / 100.;
+                (low.map(|low| low - widen), high.map(|high| high + widen)).contains(&shown)
+            }
+        }
+    }
+}
+
+/// The similarity, in points from -100 to 100, that a guess was given.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Similarity {
+    /// A value as Semantle showed it.
+    Exact(f32),
+    /// Somewhere between two bounds, as given by a band or an inequality.
+    Range(Bound<f32>, Bound<f32>),
+}
+
+impl Similarity {
+    pub fn parse(s: &str) -> Option<Self> {
+        use Bound::*;
+        let value = |s: &str| s.parse::<f32>().ok().filter(|value| value.is_finite());
+        if let Ok(band) = s.parse::<Band>() {
+            let (low, high) = band.range();
+            return Some(Similarity::Range(low, high));
+        }
+        let range = if let Some(low) = s.strip_prefix(">=") {
+            (Included(value(low)?), Unbounded)
+        } else if let Some(low) = s.strip_prefix('>') {
+            (Excluded(value(low)?), Unbounded)
+        } else if let Some(high) = s.strip_prefix("<=") {
+            (Unbounded, Included(value(high)?))
+        } else if let Some(high) = s.strip_prefix('<') {
+            (Unbounded, Excluded(value(high)?))
+        } else if let Some((low, high)) = s.split_once("..") {
+            (Included(value(low)?), Excluded(value(high)?))
+        } else {
+            return value(s).map(Similarity::Exact);
+        };
+        Some(Similarity::Range(range.0, range.1))
+    }
+}
+
+impl fmt::Display for Similarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Bound::*;
+        match *self {
+            Similarity::Exact(value) => write!(f, "{value}"),
+            Similarity::Range(low, high) => {
+                if let Some(band) = Band::ALL
+                    .into_iter()
+                    .find(|band| band.range() == (low, high))
+                {
+                    return write!(f, "{band}");
+                }
+                match (low, high) {
+                    (Included(low), Excluded(high)) => write!(f, "{low}..{high}"),
+                    (Included(low), Unbounded) => write!(f, ">={low}"),
+                    (Excluded(low), Unbounded) => write!(f, ">{low}"),
+                    (Unbounded, Included(high)) => write!(f, "<={high}"),
+                    (Unbounded, Excluded(high)) => write!(f, "<{high}"),
+                    (low, high) => write!(f, "{low:?}..{high:?}"),
+                }
+            }
+        }
+    }
+}
+
+/// Semantle's rank feedback for a guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rank {
+    /// "N/1000": the guess is the answer's (1000 - N)th nearest neighbor.
+    At(usize),
+    /// The guess is not among the answer's nearest neighbors.
+    Outside,
+}
+
+impl Rank {
+    /// Parses `r876` or, for a guess outside the ranked neighbors, `r-`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.strip_prefix('r')? {
+            "-" => Some(Rank::Outside),
+            n => n.parse().ok().filter(|&n| n > 0).map(Rank::At),
+        }
+    }
+
+    /// Whether a word at `position` among the answer's neighbors gets this
+    /// rank; the answer itself is at 0. `threshold` is the 1000 in "N/1000".
+    pub fn admits(self, position: usize, threshold: usize) -> bool {
+        match self {
+            Rank::At(n) => n <= threshold && position == threshold - n,
+            Rank::Outside => position >= threshold,
+        }
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rank::At(n) => write!(f, "r{n}"),
+            Rank::Outside => write!(f, "r-"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound::*;
+
+    use super::*;
+
+    #[test]
+    fn tolerance_widens_to_the_typed_precision() {
+        let tolerance = |similarity, own| {
+            Constraint::parse("apple", similarity, own)
+                .unwrap()
+                .tolerance(0.005)
+        };
+        assert_eq!(tolerance("45.31", None), 0.005);
+        assert_eq!(tolerance("45.3", None), 0.05);
+        assert_eq!(tolerance("45", None), 0.5);
+        assert_eq!(tolerance("45", Some(0.01)), 0.01);
+
+        let constraint = Constraint::parse("apple", "45.3", None).unwrap();
+        assert!(constraint.matches(0.4526, 0.005));
+        assert!(constraint.matches(0.4534, 0.005));
+        assert!(!constraint.matches(0.4536, 0.005));
+    }
+
+    #[test]
+    fn parses_ranks() {
+        assert_eq!(Rank::parse("r876"), Some(Rank::At(876)));
+        assert_eq!(Rank::parse("r-"), Some(Rank::Outside));
+        for invalid in ["r", "r0", "876", "rx", "r-1"] {
+            assert_eq!(Rank::parse(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn ranks_admit_their_position() {
+        // The answer is at 0, so "1000/1000" is its nearest neighbor.
+        assert!(Rank::At(1000).admits(0, 1000));
+        assert!(Rank::At(999).admits(1, 1000));
+        assert!(!Rank::At(999).admits(2, 1000));
+        assert!(Rank::At(1).admits(999, 1000));
+        assert!(!Rank::At(1001).admits(0, 1000));
+        assert!(Rank::Outside.admits(1000, 1000));
+        assert!(!Rank::Outside.admits(999, 1000));
+    }
+
+    #[test]
+    fn parses_similarities() {
+        assert_eq!(Similarity::parse("45.3"), Some(Similarity::Exact(45.3)));
+        assert_eq!(Similarity::parse("-5"), Some(Similarity::Exact(-5.)));
+        assert_eq!(
+            Similarity::parse(">30"),
+            Some(Similarity::Range(Excluded(30.), Unbounded))
+        );
+        assert_eq!(
+            Similarity::parse(">=30"),
+            Some(Similarity::Range(Included(30.), Unbounded))
+        );
+        assert_eq!(
+            Similarity::parse("<-5"),
+            Some(Similarity::Range(Unbounded, Excluded(-5.)))
+        );
+        assert_eq!(
+            Similarity::parse("<=-5"),
+            Some(Similarity::Range(Unbounded, Included(-5.)))
+        );
+        assert_eq!(
+            Similarity::parse("20..30"),
+            Some(Similarity::Range(Included(20.), Excluded(30.)))
+        );
+        let (low, high) = Band::Tepid.range();
+        assert_eq!(
+            Similarity::parse("tepid"),
+            Some(Similarity::Range(low, high))
+        );
+        for invalid in ["", "warm", ">", "1..", "nan", "inf", ">x"] {
+            assert_eq!(Similarity::parse(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn similarities_display_as_typed() {
+        for typed in ["tepid", ">30", ">=30", "<-5", "<=-5", "22..31"] {
+            assert_eq!(Similarity::parse(typed).unwrap().to_string(), typed);
+        }
+        let constraint = Constraint::parse("apple", "45.30", None).unwrap();
+        assert_eq!(constraint.typed_similarity(), "45.30");
+    }
+
+    #[test]
+    fn ranges_only_widen_by_their_own_tolerance() {
+        let range = Constraint::parse("apple", ">30", None).unwrap();
+        assert!(range.matches(0.3001, 0.5));
+        assert!(!range.matches(0.2999, 0.5));
+        let widened = Constraint::parse("apple", ">30", Some(1.)).unwrap();
+        assert!(widened.matches(0.2901, 0.));
+    }
+
+    #[test]
+    fn bands_go_by_the_rounded_similarity() {
+        let band = |name| Constraint::parse("apple", name, None).unwrap();
+        // Shown as 20.00 and 30.00.
+        assert!(band("tepid").matches(0.19996, 0.005));
+        assert!(band("toasty").matches(0.29996, 0.005));
+        assert!(!band("cold").matches(0.19996, 0.005));
+        assert!(!band("tepid").matches(0.29996, 0.005));
+        // Shown as 19.99.
+        assert!(band("cold").matches(0.19994, 0.005));
+        assert!(band(">=20").matches(0.19996, 0.005));
+    }
+}
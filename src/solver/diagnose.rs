@@ -0,0 +1,100 @@
+use itertools::Itertools;
+
+use crate::solver::SolverState;
+
+/// Subsets of the log tried when looking for a smaller conflict than the one
+/// found by dropping entries one at a time.
+const SUBSET_LIMIT: usize = 10_000;
+
+/// Finding out why no word fits the log.
+impl SolverState<'_> {
+    /// The fewest log entries that no word fits together, or `None` if some
+    /// word fits every entry. With too many entries to try every subset this
+    /// is a conflict none of whose entries can be dropped, but not always
+    /// the smallest one.
+    pub(crate) fn conflict(&mut self) -> Option<Vec<usize>> {
+        if !self.candidates.is_empty() {
+            return None;
+        }
+        let mut conflict = (0..self.log.len()).collect::<Vec<_>>();
+        let mut i = 0;
+        while i < conflict.len() {
+            let mut without = conflict.clone();
+            without.remove(i);
+            if self.fitting(&without).is_empty() {
+                conflict = without;
+            } else {
+                i += 1;
+            }
+        }
+        let entries = self.log.len();
+        for size in 1..conflict.len() {
+            if binomial(entries, size) > SUBSET_LIMIT {
+                break;
+            }
+            for subset in (0..entries).combinations(size) {
+                if self.fitting(&subset).is_empty() {
+                    return Some(subset);
+                }
+            }
+        }
+        Some(conflict)
+    }
+
+    /// How many words would fit the log with each entry dropped in turn.
+    pub(crate) fn without_each(&mut self) -> Vec<usize> {
+        (0..self.log.len())
+            .map(|entry| {
+                let others = (0..self.log.len())
+                    .filter(|&other| other != entry)
+                    .collect::<Vec<_>>();
+                self.fitting(&others).len()
+            })
+            .collect()
+    }
+}
+
+fn binomial(n: usize, k: usize) -> usize {
+    (0..k).fold(1usize, |acc, i| acc.saturating_mul(n - i) / (i + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::feedback;
+    use crate::solver::tests::settings;
+    use crate::solver::tests::store;
+    use crate::solver::Solver;
+
+    #[test]
+    fn finds_no_conflict_while_words_are_left() {
+        let store = store();
+        let mut solver = Solver::new(&store, settings());
+        solver.execute(&feedback(&store, 5, 6)).unwrap();
+        assert_eq!(solver.state_mut().conflict(), None);
+    }
+
+    #[test]
+    fn finds_the_smallest_conflict() {
+        let store = store();
+        let mut solver = Solver::new(&store, settings());
+        // Each entry is true of one of two answers, and the conflict is
+        // between the two exact values; the ranges fit either answer.
+        solver.execute("w w1 >-100").unwrap();
+        solver.execute(&feedback(&store, 60, 61)).unwrap();
+        solver.execute("w w2 <=100").unwrap();
+        solver.execute(&feedback(&store, 70, 71)).unwrap();
+        let state = solver.state_mut();
+        assert!(state.candidates.is_empty());
+        assert_eq!(state.conflict(), Some(vec![1, 3]));
+        assert_eq!(state.without_each()[0], 0);
+        assert!(state.without_each()[3] > 0);
+    }
+
+    #[test]
+    fn counts_subsets() {
+        assert_eq!(binomial(5, 0), 1);
+        assert_eq!(binomial(5, 2), 10);
+        assert_eq!(binomial(30, 15), 155_117_520);
+    }
+}
@@ -0,0 +1,166 @@
+use std::ops::Bound;
+
+use ndarray::Array1;
+use ndarray::Array2;
+
+use crate::embeddings::EmbeddingStore;
+use crate::solver::Constraint;
+use crate::solver::Similarity;
+
+/// Estimates the answer's vector from the log. Each entry says that the dot
+/// product of the answer with the word's vector is its similarity over 100,
+/// so the estimate is the shortest vector that fits those equations, or if
+/// none of at most unit length does, the unit vector that fits them best in
+/// the least-squares sense. Entries with a one-sided range or rank feedback alone
+/// say too little to be used; a bounded range counts as its midpoint.
+///
+/// Returns `None` when no entry can be used.
+pub(crate) fn estimate(store: &EmbeddingStore, log: &[Constraint]) -> Option<Array1<f32>> {
+    let (indices, targets): (Vec<u32>, Vec<f64>) = log
+        .iter()
+        .filter_map(|constraint| {
+            let value = match constraint.similarity {
+                Similarity::Exact(value) => value,
+                Similarity::Range(
+                    Bound::Included(low) | Bound::Excluded(low),
+                    Bound::Included(high) | Bound::Excluded(high),
+                ) => (low + high) / 2.,
+                Similarity::Range(..) => return None,
+            };
+            Some((store.index(&constraint.word)?, value as f64 / 100.))
+        })
+        .unzip();
+    if indices.is_empty() {
+        return None;
+    }
+    // The answer lies in the span of the logged vectors, since any part
+    // outside it changes no similarity, so the problem is solved in the
+    // coordinates of that span through their Gram matrix.
+    // With more entries than dimensions the Gram matrix is nearly singular
+    // and the coefficients large, so everything is done at full precision.
+    let rows = store.rows(&indices).mapv(f64::from);
+    let gram = rows.dot(&rows.t());
+    let targets = Array1::from(targets);
+    let solve = |lambda: f64| {
+        let coefficients = solve(&gram, lambda, &targets)?;
+        let norm = coefficients.dot(&gram.dot(&coefficients)).sqrt();
+        Some((coefficients, norm))
+    };
+    // The shortest exact fit is the estimate if it is no longer than a unit
+    // vector. Otherwise the equations contradict each other, and damping
+    // them until the fit has unit length gives the closest unit vector.
+    let trace = gram.diag().sum();
+    let mut low = 1e-9 * trace.max(1.);
+    let (mut coefficients, mut norm) = solve(low)?;
+    if norm > 1. {
+        let mut high = trace.max(1.);
+        while solve(high)?.1 > 1. {
+            high *= 2.;
+        }
+        for _ in 0..60 {
+            let mid = (low + high) / 2.;
+            (coefficients, norm) = solve(mid)?;
+            if norm > 1. {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+    }
+    let estimate = (coefficients / norm.max(1.)).dot(&rows).mapv(|x| x as f32);
+    Some(estimate)
+}
+
+/// Solves `(gram + lambda I) x = targets` by Gaussian elimination with
+/// partial pivoting.
+fn solve(gram: &Array2<f64>, lambda: f64, targets: &Array1<f64>) -> Option<Array1<f64>> {
+    let n = targets.len();
+    let mut a = gram.clone();
+    a.diag_mut().mapv_inplace(|d| d + lambda);
+    let mut b = targets.clone();
+    for column in 0..n {
+        let pivot =
+            (column..n).max_by(|&i, &j| a[[i, column]].abs().total_cmp(&a[[j, column]].abs()))?;
+        if a[[pivot, column]].abs() < 1e-12 {
+            return None;
+        }
+        for k in 0..n {
+            a.swap([column, k], [pivot, k]);
+        }
+        b.swap(column, pivot);
+        for row in column + 1..n {
+            let factor = a[[row, column]] / a[[column, column]];
+            for k in column..n {
+                a[[row, k]] -= factor * a[[column, k]];
+            }
+            b[row] -= factor * b[column];
+        }
+    }
+    let mut x = Array1::zeros(n);
+    for row in (0..n).rev() {
+        let rest = (row + 1..n).map(|k| a[[row, k]] * x[k]).sum::<f64>();
+        x[row] = (b[row] - rest) / a[[row, row]];
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+    use crate::solver::tests::store;
+
+    #[test]
+    fn solves_linear_systems() {
+        let gram = array![[2., 1.], [1., 3.]];
+        let x = solve(&gram, 0., &array![3., 5.]).unwrap();
+        assert!((x[0] - 0.8).abs() < 1e-12);
+        assert!((x[1] - 1.4).abs() < 1e-12);
+        // The damping is added to the diagonal.
+        let x = solve(&gram, 1., &array![4., 5.]).unwrap();
+        assert!((x[0] - 1.).abs() < 1e-12);
+        assert!((x[1] - 1.).abs() < 1e-12);
+        assert!(solve(&array![[1., 1.], [1., 1.]], 0., &array![1., 1.]).is_none());
+    }
+
+    #[test]
+    fn estimates_the_answer_from_its_similarities() {
+        let store = store();
+        let answer = 123;
+        let sims = store.similarities_to(answer);
+        let log = (0..12)
+            .map(|guess| {
+                let similarity = format!("{:.2}", sims[guess as usize] * 100.);
+                Constraint::parse(store.word(guess), &similarity, None).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let estimate = estimate(&store, &log).unwrap();
+        let norm = estimate.dot(&estimate).sqrt();
+        assert!(norm <= 1. + 1e-6, "norm {norm}");
+        let closeness = estimate.dot(&store.matrix().row(answer as usize)) / norm;
+        assert!(closeness > 0.99, "closeness {closeness}");
+    }
+
+    #[test]
+    fn needs_a_usable_entry() {
+        let store = store();
+        let log = [Constraint::parse("w1", ">30", None).unwrap()];
+        assert!(estimate(&store, &log).is_none());
+        assert!(estimate(&store, &[]).is_none());
+    }
+
+    #[test]
+    fn short_fits_keep_the_logged_similarities() {
+        let store = store();
+        let log = [
+            Constraint::parse("w2", "10", None).unwrap(),
+            Constraint::parse("w3", "-20", None).unwrap(),
+        ];
+        let estimate = estimate(&store, &log).unwrap();
+        for (index, value) in [(2, 0.1), (3, -0.2)] {
+            let sim = estimate.dot(&store.matrix().row(index));
+            assert!((sim - value).abs() < 1e-4, "w{index} at {sim}");
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::solver::Constraint;
+
+/// The parts of a [`SolverState`](crate::solver::SolverState) that commands
+/// change, kept whole so that going back to them needs no re-filtering.
+#[derive(Debug, Clone)]
+pub(crate) struct Snapshot {
+    pub(crate) log: Vec<Constraint>,
+    pub(crate) candidates: Vec<u32>,
+    pub(crate) matches: Vec<Arc<Vec<u32>>>,
+    pub(crate) tolerance: f32,
+    pub(crate) rank_threshold: usize,
+}
+
+/// Earlier and undone states of a session, and the ones saved by name.
+#[derive(Debug, Default)]
+pub(crate) struct History {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    pub(crate) checkpoints: HashMap<String, Snapshot>,
+}
+
+impl History {
+    /// Remembers the state from before a change. A new change forgets
+    /// whatever was undone.
+    pub(crate) fn record(&mut self, snapshot: Snapshot) {
+        self.undo.push(snapshot);
+        self.redo.clear();
+    }
+
+    /// Swaps `current` for the state before the last change.
+    pub(crate) fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    /// Swaps `current` for the state the last undo went back from.
+    pub(crate) fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        Some(next)
+    }
+}
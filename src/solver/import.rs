@@ -0,0 +1,141 @@
+use crate::band::Band;
+use crate::solver::CommandOutput;
+use crate::solver::Constraint;
+use crate::solver::Rank;
+use crate::solver::SolverState;
+
+/// One row of a guess table.
+struct Row<'a> {
+    word: String,
+    similarity: &'a str,
+    rank: Option<Rank>,
+}
+
+/// Importing guesses copied from a Semantle page.
+impl SolverState<'_> {
+    /// Logs every guess in a table copied from the Semantle website, with
+    /// rows like `12 apple 45.21 876/1000` or `13 pear 21.05 (tepid)`. A
+    /// temperature in the last column means the guess was not ranked. Lines
+    /// that are not guesses, such as the header, are skipped, and so are
+    /// words that are already logged. The import is undone as a whole.
+    pub fn import(&mut self, text: &str) -> CommandOutput {
+        let mut added = Vec::new();
+        let mut unknown = Vec::new();
+        let mut skipped = Vec::new();
+        let mut recorded = false;
+        for row in text.lines().filter_map(parse_row) {
+            if !self.store.contains(&row.word) {
+                unknown.push(row.word);
+                continue;
+            }
+            if self.log.iter().any(|c| c.word == row.word) {
+                skipped.push(row.word);
+                continue;
+            }
+            let Some(mut constraint) = Constraint::parse(&row.word, row.similarity, None) else {
+                continue;
+            };
+            constraint.rank = row.rank;
+            if !recorded {
+                self.record();
+                recorded = true;
+            }
+            self.push(constraint);
+            added.push(row.word);
+        }
+        CommandOutput::Imported {
+            added,
+            unknown,
+            skipped,
+        }
+    }
+}
+
+fn parse_row(line: &str) -> Option<Row<'_>> {
+    let terms = line
+        .split(|c: char| c.is_whitespace() || c == '|' || c == '│')
+        .filter(|term| !term.is_empty())
+        .collect::<Vec<_>>();
+    let position = terms
+        .windows(2)
+        .position(|pair| pair[0].parse::<f32>().is_err() && pair[1].parse::<f32>().is_ok())?;
+    let rank = terms[position + 2..].iter().find_map(|term| {
+        if let Some((rank, _)) = term.split_once('/') {
+            return rank.parse().ok().map(Rank::At);
+        }
+        let band = term.trim_start_matches('(').trim_end_matches(')');
+        band.to_lowercase()
+            .parse::<Band>()
+            .ok()
+            .map(|_| Rank::Outside)
+    });
+    Some(Row {
+        word: terms[position].to_lowercase(),
+        similarity: terms[position + 1],
+        rank,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::commands::SolverCommand;
+    use crate::solver::commands::Undo;
+    use crate::solver::tests::settings;
+    use crate::solver::tests::store;
+
+    fn parse(line: &str) -> Option<(String, &str, Option<Rank>)> {
+        parse_row(line).map(|row| (row.word, row.similarity, row.rank))
+    }
+
+    #[test]
+    fn parses_rows() {
+        assert_eq!(
+            parse("12\tApple\t45.21\t876/1000"),
+            Some(("apple".to_string(), "45.21", Some(Rank::At(876))))
+        );
+        assert_eq!(
+            parse("13 pear 21.05 (tepid)"),
+            Some(("pear".to_string(), "21.05", Some(Rank::Outside)))
+        );
+        assert_eq!(
+            parse("│ 14 plum -3.10 │"),
+            Some(("plum".to_string(), "-3.10", None))
+        );
+        assert_eq!(
+            parse("| 15 | fig | 33.00 | 12/1000 |"),
+            Some(("fig".to_string(), "33.00", Some(Rank::At(12))))
+        );
+    }
+
+    #[test]
+    fn skips_other_lines() {
+        assert_eq!(parse("#\tGuess\tSimilarity\tGetting close?"), None);
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("12 13"), None);
+    }
+
+    #[test]
+    fn imports_a_table() {
+        let store = store();
+        let mut state = SolverState::new(&store, settings());
+        state.import("1 w1 10.00\n");
+        let table = "#\tGuess\tSimilarity\tGetting close?\n\
+                     3\tw2\t45.21\t12/1000\n\
+                     2\tnope\t10.00\t(cold)\n\
+                     1\tw1\t10.00\t(cold)\n";
+        assert_eq!(
+            state.import(table),
+            CommandOutput::Imported {
+                added: vec!["w2".to_string()],
+                unknown: vec!["nope".to_string()],
+                skipped: vec!["w1".to_string()],
+            }
+        );
+        assert_eq!(state.log.len(), 2);
+        assert_eq!(state.log[1].rank, Some(Rank::At(12)));
+        // The whole import is undone at once.
+        Undo.run(&[], &mut state).unwrap();
+        assert_eq!(state.log.len(), 1);
+    }
+}
@@ -0,0 +1,501 @@
+use std::collections::HashMap;
+use std::mem;
+use std::sync::Arc;
+
+use rayon::prelude::*;
+
+use history::History;
+use history::Snapshot;
+
+use crate::embeddings::EmbeddingStore;
+use crate::settings::Settings;
+use crate::similarity::rank_in;
+
+pub mod commands;
+mod constraint;
+mod diagnose;
+mod geometric;
+mod history;
+mod import;
+mod mode;
+mod output;
+mod posterior;
+mod recommend;
+mod save;
+
+pub use commands::CommandError;
+pub use commands::CommandOutput;
+pub use commands::SolverCommand;
+pub use constraint::Constraint;
+pub use constraint::Rank;
+pub use constraint::Similarity;
+pub use mode::Mode;
+pub use recommend::Metric;
+
+/// Rank feedback is only checked once the similarities leave at most this
+/// many candidates, since checking a candidate scans the whole vocabulary.
+pub const RANK_LIMIT: usize = 1000;
+
+/// Everything a [`SolverCommand`] can read or change: the logged guesses and
+/// the words that are still consistent with them. Candidates are kept as
+/// indices into the shared [`EmbeddingStore`], in vocabulary order.
+pub struct SolverState<'a> {
+    pub(crate) store: &'a EmbeddingStore,
+    pub(crate) settings: Settings,
+    pub(crate) candidates: Vec<u32>,
+    pub(crate) log: Vec<Constraint>,
+    /// The words matching each constraint in `log` by similarity, computed
+    /// once per constraint. The candidates are their intersection.
+    pub(crate) matches: Vec<Arc<Vec<u32>>>,
+    /// Where a word ranks among a candidate's neighbors, by candidate and
+    /// word.
+    rank_positions: HashMap<(u32, u32), usize>,
+    pub(crate) history: History,
+    pub(crate) mode: Mode,
+    /// The best first guesses for the model by metric and number of words
+    /// searched, once they have been asked for.
+    pub(crate) openings: HashMap<(Metric, usize), Vec<(u32, f32)>>,
+}
+
+impl<'a> SolverState<'a> {
+    pub fn new(store: &'a EmbeddingStore, settings: Settings) -> Self {
+        SolverState {
+            store,
+            settings,
+            candidates: store.indices().collect(),
+            log: Vec::new(),
+            matches: Vec::new(),
+            rank_positions: HashMap::new(),
+            history: History::default(),
+            mode: Mode::Strict,
+            openings: HashMap::new(),
+        }
+    }
+
+    pub fn store(&self) -> &'a EmbeddingStore {
+        self.store
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn log(&self) -> &[Constraint] {
+        &self.log
+    }
+
+    pub fn candidates(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.candidates.iter().map(|&index| self.store.word(index))
+    }
+
+    /// Whether rank feedback is logged but not yet checked, because too many
+    /// candidates are left. See [`RANK_LIMIT`].
+    pub fn ranks_deferred(&self) -> bool {
+        self.candidates.len() > RANK_LIMIT && self.log.iter().any(|c| c.rank.is_some())
+    }
+
+    /// The 1000 in "N/1000", limited to the size of the vocabulary.
+    pub fn rank_threshold(&self) -> usize {
+        self.settings.rank_threshold.clamp(1, self.store.len())
+    }
+
+    pub(crate) fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            log: self.log.clone(),
+            candidates: self.candidates.clone(),
+            matches: self.matches.clone(),
+            tolerance: self.settings.tolerance,
+            rank_threshold: self.settings.rank_threshold,
+        }
+    }
+
+    pub(crate) fn restore(&mut self, snapshot: Snapshot) {
+        self.log = snapshot.log;
+        self.candidates = snapshot.candidates;
+        self.matches = snapshot.matches;
+        self.settings.tolerance = snapshot.tolerance;
+        self.settings.rank_threshold = snapshot.rank_threshold;
+    }
+
+    /// Saves the current state for `undo`. Commands call this just before
+    /// they change the log, the candidates or the tolerance.
+    pub(crate) fn record(&mut self) {
+        let snapshot = self.snapshot();
+        self.history.record(snapshot);
+    }
+
+    /// Logs a constraint and narrows the candidates down to its matches.
+    pub(crate) fn push(&mut self, constraint: Constraint) {
+        let matches = self.matching(&constraint);
+        let mut candidates = mem::take(&mut self.candidates);
+        candidates.retain(|index| matches.binary_search(index).is_ok());
+        self.log.push(constraint);
+        self.matches.push(matches);
+        // Ranks put off while there were too many candidates are checked as
+        // soon as there are few enough; the ones already checked are cached.
+        let entries = (0..self.log.len()).collect::<Vec<_>>();
+        self.filter_ranks(&mut candidates, &entries);
+        self.candidates = candidates;
+    }
+
+    /// Replaces the constraint at `position`, recomputing only its matches.
+    pub(crate) fn replace(&mut self, position: usize, constraint: Constraint) {
+        self.matches[position] = self.matching(&constraint);
+        self.log[position] = constraint;
+        self.update_words();
+    }
+
+    pub(crate) fn remove(&mut self, position: usize) {
+        self.log.remove(position);
+        self.matches.remove(position);
+        self.update_words();
+    }
+
+    /// Changes the session's tolerance, recomputing the matches of the
+    /// constraints that use it.
+    pub(crate) fn set_tolerance(&mut self, tolerance: f32) {
+        self.settings.tolerance = tolerance;
+        for position in 0..self.log.len() {
+            let constraint = &self.log[position];
+            if constraint.tolerance.is_none()
+                && matches!(constraint.similarity, Similarity::Exact(_))
+            {
+                self.matches[position] = self.matching(constraint);
+            }
+        }
+        self.update_words();
+    }
+
+    /// The words matching a constraint's similarity, in vocabulary order.
+    fn matching(&self, constraint: &Constraint) -> Arc<Vec<u32>> {
+        let sims = self
+            .store
+            .similarities_to(self.store.index(&constraint.word).unwrap());
+        let tolerance = self.settings.tolerance;
+        Arc::new(
+            self.store
+                .indices()
+                .filter(|&index| constraint.matches(sims[index as usize], tolerance))
+                .collect(),
+        )
+    }
+
+    /// Rebuilds the candidates from the cached matches of every constraint.
+    pub(crate) fn update_words(&mut self) {
+        let entries = (0..self.log.len()).collect::<Vec<_>>();
+        self.candidates = self.fitting(&entries);
+    }
+
+    /// The words that fit the log entries at `entries`, from their cached
+    /// matches.
+    pub(crate) fn fitting(&mut self, entries: &[usize]) -> Vec<u32> {
+        let sets = entries
+            .iter()
+            .map(|&entry| &self.matches[entry])
+            .collect::<Vec<_>>();
+        let mut fitting = match sets.iter().min_by_key(|matches| matches.len()) {
+            Some(smallest) => smallest
+                .iter()
+                .copied()
+                .filter(|index| {
+                    sets.iter()
+                        .all(|matches| matches.binary_search(index).is_ok())
+                })
+                .collect(),
+            None => self.store.indices().collect(),
+        };
+        self.filter_ranks(&mut fitting, entries);
+        fitting
+    }
+
+    /// Drops the `candidates` that contradict the rank feedback in the log
+    /// entries at `entries`. Checking a candidate needs its similarity to
+    /// every word, so positions are remembered across calls, and nothing is
+    /// checked while there are more than [`RANK_LIMIT`] candidates.
+    fn filter_ranks(&mut self, candidates: &mut Vec<u32>, entries: &[usize]) {
+        if candidates.len() > RANK_LIMIT {
+            return;
+        }
+        let store = self.store;
+        let ranks = entries
+            .iter()
+            .filter_map(|&entry| {
+                let constraint = &self.log[entry];
+                Some((store.index(&constraint.word).unwrap(), constraint.rank?))
+            })
+            .collect::<Vec<_>>();
+        if ranks.is_empty() {
+            return;
+        }
+        let threshold = self.rank_threshold();
+        let positions = &mut self.rank_positions;
+        let missing = candidates
+            .iter()
+            .copied()
+            .filter(|&candidate| {
+                ranks
+                    .iter()
+                    .any(|&(word, _)| !positions.contains_key(&(candidate, word)))
+            })
+            .collect::<Vec<_>>();
+        let found = missing
+            .par_iter()
+            .flat_map_iter(|&candidate| {
+                let sims = store.similarities_to(candidate);
+                ranks
+                    .iter()
+                    .map(|&(word, _)| {
+                        let position = if candidate == word {
+                            0
+                        } else {
+                            rank_in(&sims, candidate, word)
+                        };
+                        ((candidate, word), position)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        positions.extend(found);
+        candidates.retain(|&candidate| {
+            ranks
+                .iter()
+                .all(|&(word, rank)| rank.admits(positions[&(candidate, word)], threshold))
+        });
+    }
+}
+
+/// An interactive solving session that dispatches input lines to the
+/// registered commands.
+pub struct Solver<'a> {
+    state: SolverState<'a>,
+    commands: Vec<Box<dyn SolverCommand>>,
+    /// Whether the last command's output prints as JSON.
+    json: bool,
+}
+
+impl<'a> Solver<'a> {
+    pub fn new(store: &'a EmbeddingStore, settings: Settings) -> Self {
+        Solver {
+            json: settings.json,
+            state: SolverState::new(store, settings),
+            commands: commands::all(),
+        }
+    }
+
+    pub fn state(&self) -> &SolverState<'a> {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut SolverState<'a> {
+        &mut self.state
+    }
+
+    pub fn commands(&self) -> &[Box<dyn SolverCommand>] {
+        &self.commands
+    }
+
+    /// Runs one line of solver input. Blank lines produce
+    /// [`CommandOutput::None`]. A `-j` anywhere on the line asks for the
+    /// output as JSON, as the `json` setting does for every command.
+    pub fn execute(&mut self, line: &str) -> Result<CommandOutput, CommandError> {
+        let lowercase = line.to_lowercase();
+        let terms = lowercase.split_whitespace().collect::<Vec<_>>();
+        self.json = self.state.settings.json || terms.contains(&"-j");
+        let terms = terms
+            .into_iter()
+            .filter(|&term| term != "-j")
+            .collect::<Vec<_>>();
+        let Some(name) = terms.first() else {
+            return Ok(CommandOutput::None);
+        };
+        let command = self
+            .commands
+            .iter()
+            .find(|c| c.name() == *name)
+            .ok_or(CommandError::UnknownCommand)?;
+        if command.case_sensitive() {
+            let terms = line
+                .split_whitespace()
+                .filter(|&term| term != "-j")
+                .collect::<Vec<_>>();
+            return command.run(&terms[1..], &mut self.state);
+        }
+        command.run(&terms[1..], &mut self.state)
+    }
+
+    /// Logs the guesses in a table pasted after [`CommandOutput::Paste`].
+    pub fn import(&mut self, text: &str) -> CommandOutput {
+        self.state.import(text)
+    }
+
+    /// Prints a command's output, in JSON if it was asked for.
+    pub fn print(&self, output: &CommandOutput) {
+        if self.json {
+            output::print_json(output, &self.commands);
+        } else {
+            output::print(output, &self.commands);
+        }
+    }
+
+    /// Whether the last command asked for JSON output.
+    pub fn json(&self) -> bool {
+        self.json
+    }
+
+    /// Prints why a command failed, in JSON if it was asked for.
+    pub fn print_error(&self, error: &CommandError) {
+        if self.json {
+            println!("{}", serde_json::json!({ "error": error.to_string() }));
+        } else {
+            println!("{error}");
+        }
+    }
+}
+
+pub(crate) fn filter_embeddings(similarity: f32, target_val: f32, tolerance: f32) -> bool {
+    let res = similarity * 100.0;
+    res >= target_val - tolerance && res < target_val + tolerance
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use itertools::Itertools;
+    use ndarray::Array2;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    /// A small store of random unit vectors, `w0` to `w199`.
+    pub(crate) fn store() -> EmbeddingStore {
+        store_of(200)
+    }
+
+    /// A store of `len` random unit vectors.
+    pub(crate) fn store_of(len: usize) -> EmbeddingStore {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut matrix = Array2::from_shape_fn((len, 8), |_| rng.gen_range(-1f32..1.));
+        for mut row in matrix.rows_mut() {
+            let norm = row.dot(&row).sqrt();
+            row /= norm;
+        }
+        let words = (0..len).map(|i| format!("w{i}")).collect();
+        EmbeddingStore::from_parts(words, matrix).unwrap()
+    }
+
+    pub(crate) fn settings() -> Settings {
+        Settings {
+            rank_threshold: 20,
+            ..Settings::default()
+        }
+    }
+
+    /// The `w` command logging `guess` as Semantle would show it for
+    /// `answer`: rounded to two decimals, with its rank if it has one.
+    pub(crate) fn feedback(store: &EmbeddingStore, answer: u32, guess: u32) -> String {
+        let sims = store.similarities_to(answer);
+        let position = rank_in(&sims, answer, guess);
+        let threshold = settings().rank_threshold;
+        let rank = match position < threshold {
+            true => format!("r{}", threshold - position),
+            false => "r-".to_string(),
+        };
+        format!(
+            "w {} {:.2} {rank}",
+            store.word(guess),
+            sims[guess as usize] * 100.
+        )
+    }
+
+    #[test]
+    fn answer_survives_its_own_feedback() {
+        let store = store();
+        for answer in [0, 57, 199] {
+            let mut solver = Solver::new(&store, settings());
+            // The third nearest neighbor makes sure one guess is ranked.
+            let neighbor = store.neighbors(answer, 3)[2].0;
+            let guesses = [1, 2, 3, 4, neighbor]
+                .into_iter()
+                .filter(|&guess| guess != answer)
+                .unique();
+            for guess in guesses {
+                solver.execute(&feedback(&store, answer, guess)).unwrap();
+            }
+            assert!(
+                solver
+                    .state()
+                    .candidates()
+                    .any(|word| word == store.word(answer)),
+                "{} was ruled out",
+                store.word(answer)
+            );
+        }
+    }
+
+    #[test]
+    fn undo_and_redo_give_back_the_same_candidates() {
+        let store = store();
+        let mut solver = Solver::new(&store, settings());
+        solver.execute("w w11 >0").unwrap();
+        let first = solver.state().candidates.clone();
+        solver.execute(&feedback(&store, 10, 12)).unwrap();
+        let second = solver.state().candidates.clone();
+        assert_ne!(first, second);
+
+        solver.execute("undo").unwrap();
+        assert_eq!(solver.state().candidates, first);
+        solver.execute("redo").unwrap();
+        assert_eq!(solver.state().candidates, second);
+        solver.execute("undo").unwrap();
+        solver.execute("undo").unwrap();
+        assert_eq!(solver.state().candidates.len(), store.len());
+        assert_eq!(solver.execute("undo"), Err(CommandError::NothingToUndo));
+    }
+
+    /// The guess among `guesses` that ranks closest to `answer`.
+    fn closest(store: &EmbeddingStore, answer: u32, guesses: &[u32]) -> u32 {
+        let sims = store.similarities_to(answer);
+        *guesses
+            .iter()
+            .min_by_key(|&&guess| rank_in(&sims, answer, guess))
+            .unwrap()
+    }
+
+    #[test]
+    fn why_returns_the_minimal_pair() {
+        let store = store();
+        let mut solver = Solver::new(&store, settings());
+        // Two true entries for one answer, then one for another answer that
+        // only contradicts the closer of them.
+        let guess = closest(&store, 30, &[40, 41]);
+        solver.execute("w w40 >-100").unwrap();
+        solver.execute(&feedback(&store, 30, guess)).unwrap();
+        solver.execute(&feedback(&store, 90, 42)).unwrap();
+        assert_eq!(solver.state().candidates.len(), 0);
+
+        let Ok(CommandOutput::Diagnosis { conflict, .. }) = solver.execute("why") else {
+            panic!("why didn't diagnose");
+        };
+        let words = conflict.iter().map(|c| c.word.as_str()).collect::<Vec<_>>();
+        assert_eq!(words, [store.word(guess), "w42"]);
+    }
+
+    #[test]
+    fn ranks_wait_for_few_enough_candidates() {
+        let store = store_of(RANK_LIMIT * 2);
+        let mut solver = Solver::new(&store, settings());
+        solver.execute("w w1 >-100 r-").unwrap();
+        assert_eq!(solver.state().candidates.len(), store.len());
+        assert!(solver.state().ranks_deferred());
+
+        solver.execute(&feedback(&store, 500, 2)).unwrap();
+        let state = solver.state();
+        assert!(!state.ranks_deferred());
+        assert!(state.candidates.contains(&500));
+        assert!(state
+            .candidates
+            .iter()
+            .all(|&candidate| candidate != 1 && store.rank(candidate, 1) >= 20));
+    }
+}
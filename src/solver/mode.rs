@@ -0,0 +1,28 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// How logged similarities are matched against the vocabulary.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "name", rename_all = "lowercase")]
+pub enum Mode {
+    /// Words either fit every constraint, within its tolerance, or are out.
+    Strict,
+    /// Every reported similarity may be off by Gaussian noise with this
+    /// standard deviation, in points, and words are ranked by how likely
+    /// they make the reports.
+    Noisy { sigma: f32 },
+    /// Words are ranked by their similarity to an estimate of the answer's
+    /// vector, fitted to the logged similarities by least squares.
+    Geometric,
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mode::Strict => write!(f, "strict"),
+            Mode::Noisy { sigma } => write!(f, "noisy (σ = {sigma})"),
+            Mode::Geometric => write!(f, "geometric"),
+        }
+    }
+}
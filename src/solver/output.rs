@@ -0,0 +1,230 @@
+use serde_json::json;
+
+use super::CommandOutput;
+use super::Rank;
+use super::SolverCommand;
+use super::RANK_LIMIT;
+
+/// Prints `output` as one line of JSON. Outputs that only steer the caller
+/// print nothing.
+pub(crate) fn print_json(output: &CommandOutput, commands: &[Box<dyn SolverCommand>]) {
+    let json = match output {
+        CommandOutput::None | CommandOutput::Quit(_) | CommandOutput::Paste => return,
+        CommandOutput::Help => json!({
+            "help": commands
+                .iter()
+                .map(|c| json!({ "usage": c.usage(), "description": c.description() }))
+                .collect::<Vec<_>>()
+        })
+        .to_string(),
+        output => serde_json::to_string(output).expect("outputs serialize"),
+    };
+    println!("{json}");
+}
+
+pub(crate) fn print(output: &CommandOutput, commands: &[Box<dyn SolverCommand>]) {
+    match output {
+        CommandOutput::None | CommandOutput::Quit(_) => {}
+        CommandOutput::Help => {
+            println!("Type one of the following commands:");
+            commands.iter().for_each(|a| {
+                println!("\t{}", a.usage());
+                println!("\t\t{}", a.description())
+            });
+            println!("Add -j to any command to print its output as JSON.");
+        }
+        CommandOutput::Log {
+            entries,
+            debug: false,
+        } => {
+            println!("Here are the words and similarities you've provided so far:");
+            entries.iter().enumerate().for_each(|(i, c)| {
+                print!(
+                    "\t{}. `{}` with a similarity of `{}`",
+                    i + 1,
+                    c.word,
+                    c.typed_similarity()
+                );
+                if let Some(tolerance) = c.tolerance {
+                    print!(" ±{tolerance}");
+                }
+                match c.rank {
+                    Some(Rank::At(rank)) => println!(", ranked {rank}"),
+                    Some(Rank::Outside) => println!(", unranked"),
+                    None => println!(),
+                }
+            });
+        }
+        CommandOutput::Log {
+            entries,
+            debug: true,
+        } => println!("{:?}", entries),
+        CommandOutput::Candidates {
+            words,
+            embeddings: Some(embeddings),
+            ..
+        } => println!("{:?}", words.iter().zip(embeddings).collect::<Vec<_>>()),
+        CommandOutput::Candidates {
+            words, debug: true, ..
+        } => println!("{:?}", words),
+        CommandOutput::Candidates {
+            words,
+            ranks_deferred,
+            ..
+        } => {
+            words.iter().for_each(|k| println!("{}", k));
+            if *ranks_deferred {
+                println!(
+                    "Rank feedback is not checked until at most {RANK_LIMIT} candidates are left."
+                );
+            }
+        }
+        CommandOutput::Posterior {
+            words, debug: true, ..
+        }
+        | CommandOutput::Estimate {
+            words, debug: true, ..
+        } => println!("{:?}", words),
+        CommandOutput::Estimate { words, .. } => {
+            let width = words
+                .iter()
+                .map(|(word, _)| word.chars().count())
+                .max()
+                .unwrap_or(0);
+            words.iter().for_each(|(word, sim)| {
+                println!(
+                    "{word}{}{sim}",
+                    " ".repeat(width - word.chars().count() + 1)
+                )
+            });
+        }
+        CommandOutput::Posterior { words, .. } => {
+            let width = words
+                .iter()
+                .map(|(word, _)| word.chars().count())
+                .max()
+                .unwrap_or(0);
+            words.iter().for_each(|(word, probability)| {
+                println!(
+                    "{word}{}{:.2}%",
+                    " ".repeat(width - word.chars().count() + 1),
+                    probability * 100.
+                )
+            });
+            println!("Rank feedback is not used in noisy mode.");
+        }
+        CommandOutput::Neighbors {
+            neighbors,
+            reverse,
+            debug: false,
+        } => {
+            let spaces1 = (neighbors.len() + 1).to_string().len();
+            let spaces2 = neighbors
+                .iter()
+                .map(|(word, _)| word.chars().count())
+                .max()
+                .unwrap_or(0);
+            let mut rows = neighbors.iter().enumerate().collect::<Vec<_>>();
+            if *reverse {
+                rows.reverse();
+            }
+            rows.into_iter().for_each(|(index, (word, sim))| {
+                println!(
+                    "{}{}{word}{}{sim}",
+                    index + 1,
+                    " ".repeat(spaces1 - (index + 1).to_string().len() + 1),
+                    " ".repeat(spaces2 - word.chars().count() + 1)
+                )
+            });
+        }
+        CommandOutput::Neighbors {
+            neighbors,
+            reverse,
+            debug: true,
+        } => {
+            let mut neighbors = neighbors.iter().collect::<Vec<_>>();
+            if *reverse {
+                neighbors.reverse();
+            }
+            println!("{:?}", neighbors);
+        }
+        CommandOutput::Rank(rank) => println!("{rank}"),
+        CommandOutput::Tolerance(tolerance) => println!("Tolerance is ±{tolerance}"),
+        CommandOutput::Mode(mode) => println!("Mode is {mode}"),
+        CommandOutput::Paste => println!("Paste the guesses, then an empty line:"),
+        CommandOutput::Imported {
+            added,
+            unknown,
+            skipped,
+        } => {
+            println!("Imported {} words", added.len());
+            if !skipped.is_empty() {
+                println!("Already logged: {}", skipped.join(", "));
+            }
+            if !unknown.is_empty() {
+                println!("Not in the model: {}", unknown.join(", "));
+            }
+        }
+        CommandOutput::Saved(path) => println!("Saved to {}", path.display()),
+        CommandOutput::Loaded { path, saved_model } => {
+            println!("Loaded {}", path.display());
+            if let Some(model) = saved_model {
+                println!("Warning: the session was saved with {}", model.display());
+            }
+        }
+        CommandOutput::Checkpoints(names) if names.is_empty() => println!("No checkpoints yet"),
+        CommandOutput::Checkpoints(names) => names.iter().for_each(|name| println!("{name}")),
+        CommandOutput::Diagnosis {
+            remaining,
+            conflict,
+            dropped,
+        } => {
+            if conflict.is_empty() {
+                println!("Nothing contradicts, {remaining} candidates are left.");
+            } else {
+                println!("These words contradict each other:");
+                conflict.iter().for_each(|c| {
+                    println!(
+                        "\t`{}` with a similarity of `{}`",
+                        c.word,
+                        c.typed_similarity()
+                    )
+                });
+            }
+            if !dropped.is_empty() {
+                println!("Words left without each word:");
+                dropped.iter().for_each(|(c, left)| {
+                    println!(
+                        "\t`{}` with a similarity of `{}`: {left}",
+                        c.word,
+                        c.typed_similarity()
+                    )
+                });
+            }
+        }
+        CommandOutput::Recommendations { words, metric } => {
+            println!("The best guesses by {metric} are:");
+            let spaces1 = words.len().to_string().len();
+            let spaces2 = words
+                .iter()
+                .map(|r| r.word.chars().count())
+                .max()
+                .unwrap_or(0);
+            words.iter().enumerate().for_each(|(index, r)| {
+                println!(
+                    "{}{}{}{}{:.2}{}",
+                    index + 1,
+                    " ".repeat(spaces1 - (index + 1).to_string().len() + 1),
+                    r.word,
+                    " ".repeat(spaces2 - r.word.chars().count() + 1),
+                    r.score,
+                    if r.candidate {
+                        " (possible answer)"
+                    } else {
+                        ""
+                    }
+                )
+            });
+        }
+    }
+}
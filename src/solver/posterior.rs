@@ -0,0 +1,84 @@
+use std::ops::Bound;
+
+use ndarray::Array1;
+use ndarray::Zip;
+
+use crate::embeddings::EmbeddingStore;
+use crate::similarity::top;
+use crate::solver::Constraint;
+use crate::solver::Similarity;
+
+/// The chance that a report has nothing to do with the real similarity, such
+/// as a typo, in which case it is spread evenly over -100 to 100. This caps
+/// what a single bad entry can cost the real answer.
+const OUTLIER: f32 = 0.05;
+
+/// The `n` likeliest answers given `log`, likeliest first, with their
+/// posterior probabilities under a uniform prior. Rank feedback is not used.
+pub(crate) fn posterior(
+    store: &EmbeddingStore,
+    log: &[Constraint],
+    sigma: f32,
+    n: usize,
+) -> Vec<(u32, f32)> {
+    let mut scores = Array1::<f32>::zeros(store.len());
+    for constraint in log {
+        let sims = store.similarities_to(store.index(&constraint.word).unwrap());
+        Zip::from(&mut scores)
+            .and(&sims)
+            .par_for_each(|score, &sim| {
+                *score += log_likelihood(constraint.similarity, sim * 100., sigma)
+            });
+    }
+    let max = scores.fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+    scores.mapv_inplace(|score| (score - max).exp());
+    let total = scores.sum();
+    let probabilities = scores
+        .iter()
+        .enumerate()
+        .map(|(index, &score)| (index as u32, score / total))
+        .collect();
+    top(probabilities, n)
+}
+
+/// The log of the chance that a word at `actual` points was reported as
+/// `reported`: Gaussian noise, or with chance [`OUTLIER`] a value that could
+/// be anything.
+fn log_likelihood(reported: Similarity, actual: f32, sigma: f32) -> f32 {
+    let (noisy, uniform) = match reported {
+        Similarity::Exact(value) => {
+            let z = (value - actual) / sigma;
+            let density = (-z * z / 2.).exp() / (sigma * (2. * std::f32::consts::PI).sqrt());
+            (density, 1. / 200.)
+        }
+        Similarity::Range(low, high) => {
+            let value = |bound: Bound<f32>, unbounded: f32| match bound {
+                Bound::Included(x) | Bound::Excluded(x) => x.clamp(-100., 100.),
+                Bound::Unbounded => unbounded,
+            };
+            let cdf = |bound: Bound<f32>, unbounded: f32| match bound {
+                Bound::Included(x) | Bound::Excluded(x) => normal_cdf((x - actual) / sigma),
+                Bound::Unbounded => unbounded,
+            };
+            let width = (value(high, 100.) - value(low, -100.)).max(0.);
+            (cdf(high, 1.) - cdf(low, 0.), width / 200.)
+        }
+    };
+    ((1. - OUTLIER) * noisy + OUTLIER * uniform)
+        .max(f32::MIN_POSITIVE)
+        .ln()
+}
+
+fn normal_cdf(z: f32) -> f32 {
+    0.5 * (1. + erf(z / std::f32::consts::SQRT_2))
+}
+
+/// Abramowitz and Stegun's approximation 7.1.26, good to about 1e-7.
+fn erf(x: f32) -> f32 {
+    let t = 1. / (1. + 0.327_591_1 * x.abs());
+    let poly = t
+        * (0.254_829_6
+            + t * (-0.284_496_72 + t * (1.421_413_8 + t * (-1.453_152_1 + t * 1.061_405_4))));
+    let y = 1. - poly * (-x * x).exp();
+    y.copysign(x)
+}
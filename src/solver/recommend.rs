@@ -0,0 +1,165 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::embeddings::EmbeddingStore;
+
+/// Unless asked for more, at most this many words are scored as guesses,
+/// taken from the front of the candidates or the vocabulary, which most
+/// embedding files sort by frequency.
+pub(crate) const GUESS_LIMIT: usize = 1000;
+
+/// Guesses are scored against at most this many candidates, spread evenly
+/// over the candidate set.
+pub(crate) const ANSWER_LIMIT: usize = 2000;
+
+/// Guesses are scored this many at a time on each thread.
+const CHUNK: usize = 64;
+
+/// How a guess is scored by the way it splits the candidates into buckets
+/// that Semantle would show the same similarity for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Metric {
+    /// The expected number of candidates left after the guess. Lower is
+    /// better.
+    Expected,
+    /// The entropy of the buckets in bits. Higher is better.
+    Entropy,
+    /// The number of candidates left in the worst case, the size of the
+    /// largest bucket. Lower is better.
+    Worst,
+}
+
+impl Metric {
+    /// Scores a guess from the sizes of its buckets, which add up to
+    /// `total`.
+    fn score(self, buckets: &[usize], total: usize) -> f32 {
+        let total = total as f32;
+        match self {
+            Metric::Expected => buckets.iter().map(|&n| (n * n) as f32).sum::<f32>() / total,
+            // A single bucket sums to -0, which would print as "-0.00".
+            Metric::Entropy => buckets
+                .iter()
+                .map(|&n| n as f32 / total)
+                .map(|p| -p * p.log2())
+                .sum::<f32>()
+                .abs(),
+            Metric::Worst => buckets.iter().copied().max().unwrap_or(0) as f32,
+        }
+    }
+
+    /// Orders scores best first.
+    fn compare(self, a: f32, b: f32) -> Ordering {
+        match self {
+            Metric::Expected | Metric::Worst => a.total_cmp(&b),
+            Metric::Entropy => b.total_cmp(&a),
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Metric::Expected => "expected candidates left",
+            Metric::Entropy => "bits of information",
+            Metric::Worst => "most candidates left",
+        })
+    }
+}
+
+/// Every `n`th word of `indices`, so that at most `limit` are left.
+pub(crate) fn sample(indices: &[u32], limit: usize) -> Vec<u32> {
+    if indices.len() <= limit {
+        return indices.to_vec();
+    }
+    (0..limit)
+        .map(|i| indices[i * indices.len() / limit])
+        .collect()
+}
+
+/// Scores each of `guesses` by how it splits `candidates`, best first. Among
+/// equal scores, guesses that could be the answer come first. Large candidate
+/// sets are sampled, and counts scaled back up to their size.
+pub(crate) fn recommend(
+    store: &EmbeddingStore,
+    guesses: &[u32],
+    candidates: &[u32],
+    metric: Metric,
+) -> Vec<(u32, f32)> {
+    let answers = store.rows(&sample(candidates, ANSWER_LIMIT));
+    let scale = candidates.len() as f32 / answers.nrows() as f32;
+    // Scoring the whole vocabulary would not fit in memory at once, so each
+    // thread takes a few guesses at a time.
+    let mut scores = guesses
+        .par_chunks(CHUNK)
+        .flat_map_iter(|chunk| {
+            let sims = store.rows(chunk).dot(&answers.t());
+            chunk
+                .iter()
+                .zip(sims.rows())
+                .map(|(&guess, sims)| {
+                    let mut rounded = sims
+                        .iter()
+                        .map(|sim| (sim * 10000.).round() as i32)
+                        .collect::<Vec<_>>();
+                    rounded.sort_unstable();
+                    let buckets = rounded
+                        .chunk_by(|a, b| a == b)
+                        .map(<[i32]>::len)
+                        .collect::<Vec<_>>();
+                    let score = metric.score(&buckets, answers.nrows());
+                    match metric {
+                        Metric::Expected | Metric::Worst => (guess, score * scale),
+                        Metric::Entropy => (guess, score),
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let is_candidate = |index: &u32| candidates.binary_search(index).is_ok();
+    scores.sort_by(|a, b| {
+        metric
+            .compare(a.1, b.1)
+            .then(is_candidate(&b.0).cmp(&is_candidate(&a.0)))
+            .then(a.0.cmp(&b.0))
+    });
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_buckets() {
+        let buckets = [2, 1, 1];
+        assert_eq!(Metric::Expected.score(&buckets, 4), 1.5);
+        assert_eq!(Metric::Entropy.score(&buckets, 4), 1.5);
+        assert_eq!(Metric::Worst.score(&buckets, 4), 2.);
+    }
+
+    #[test]
+    fn a_single_bucket_has_no_information() {
+        let entropy = Metric::Entropy.score(&[5], 5);
+        assert_eq!(entropy, 0.);
+        assert!(entropy.is_sign_positive());
+        assert_eq!(format!("{entropy:.2}"), "0.00");
+    }
+
+    #[test]
+    fn orders_scores_best_first() {
+        assert_eq!(Metric::Expected.compare(1., 2.), Ordering::Less);
+        assert_eq!(Metric::Worst.compare(1., 2.), Ordering::Less);
+        assert_eq!(Metric::Entropy.compare(1., 2.), Ordering::Greater);
+    }
+
+    #[test]
+    fn samples_evenly() {
+        let indices = (0..10).collect::<Vec<_>>();
+        assert_eq!(sample(&indices, 20), indices);
+        assert_eq!(sample(&indices, 5), [0, 2, 4, 6, 8]);
+    }
+}
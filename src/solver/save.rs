@@ -0,0 +1,249 @@
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde::Serializer;
+
+use crate::solver::CommandError;
+use crate::solver::Constraint;
+use crate::solver::Rank;
+use crate::solver::SolverState;
+
+/// Version of the session file layout, bumped whenever it changes in a way
+/// older versions cannot read. Version 1 files had no vocabulary fingerprint
+/// and can still be loaded.
+const VERSION: u32 = 2;
+
+/// A solver session as it is saved to disk, as JSON.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Session {
+    version: u32,
+    model: Model,
+    tolerance: f32,
+    rank_threshold: usize,
+    log: Vec<Entry>,
+}
+
+/// The embeddings a session was solved with. Logged similarities only mean
+/// the same thing under the same model.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Model {
+    path: Option<PathBuf>,
+    words: usize,
+    dims: usize,
+    /// [`EmbeddingStore::fingerprint`](crate::EmbeddingStore::fingerprint)
+    /// in hex.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    vocabulary: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    word: String,
+    /// As it was typed, so that its precision survives.
+    similarity: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tolerance: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rank: Option<String>,
+}
+
+impl From<&Constraint> for Entry {
+    fn from(constraint: &Constraint) -> Self {
+        Entry {
+            word: constraint.word.clone(),
+            similarity: constraint.typed_similarity(),
+            tolerance: constraint.tolerance,
+            rank: constraint.rank.map(|rank| rank.to_string()),
+        }
+    }
+}
+
+/// Constraints print as JSON the same way sessions save them.
+impl Serialize for Constraint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Entry::from(self).serialize(serializer)
+    }
+}
+
+#[derive(Deserialize)]
+struct Versioned {
+    version: u32,
+}
+
+/// Saving and loading sessions.
+impl SolverState<'_> {
+    /// Writes the log and the settings it was filtered with to `path`.
+    pub fn save(&self, path: &Path) -> Result<(), CommandError> {
+        let session = Session {
+            version: VERSION,
+            model: Model {
+                path: self.store.path().map(Path::to_path_buf),
+                words: self.store.len(),
+                dims: self.store.dims(),
+                vocabulary: Some(format!("{:016x}", self.store.fingerprint())),
+            },
+            tolerance: self.settings.tolerance,
+            rank_threshold: self.settings.rank_threshold,
+            log: self.log.iter().map(Entry::from).collect(),
+        };
+        let json = serde_json::to_string_pretty(&session).expect("sessions serialize");
+        fs::write(path, json + "\n").map_err(|e| file_error(path, e))
+    }
+
+    /// Replaces the log and its settings with the session saved at `path`.
+    /// This can be undone. Returns the embeddings file the session was saved
+    /// with if it is not the one that is loaded, though their vocabularies
+    /// match.
+    pub fn load(&mut self, path: &Path) -> Result<Option<PathBuf>, CommandError> {
+        let session = self.read(path)?;
+        self.record();
+        Ok(self.apply(session))
+    }
+
+    /// Starts from the session saved at `path`. Unlike [`load`](Self::load)
+    /// this is not recorded, so `undo` cannot go back past it.
+    pub fn resume(&mut self, path: &Path) -> Result<Option<PathBuf>, CommandError> {
+        let session = self.read(path)?;
+        Ok(self.apply(session))
+    }
+
+    /// Reads and checks the session saved at `path`, with its log parsed.
+    fn read(&self, path: &Path) -> Result<(Session, Vec<Constraint>), CommandError> {
+        let json = fs::read_to_string(path).map_err(|e| file_error(path, e))?;
+        let Versioned { version } = serde_json::from_str(&json).map_err(|e| file_error(path, e))?;
+        if !(1..=VERSION).contains(&version) {
+            return Err(file_error(
+                path,
+                format!("unsupported session version {version}, expected at most {VERSION}"),
+            ));
+        }
+        let session: Session = serde_json::from_str(&json).map_err(|e| file_error(path, e))?;
+        if (session.model.words, session.model.dims) != (self.store.len(), self.store.dims()) {
+            return Err(file_error(
+                path,
+                format!(
+                    "saved with a model of {} words and {} dimensions, but {} words and {} dimensions are loaded",
+                    session.model.words,
+                    session.model.dims,
+                    self.store.len(),
+                    self.store.dims()
+                ),
+            ));
+        }
+        let fingerprint = format!("{:016x}", self.store.fingerprint());
+        if session
+            .model
+            .vocabulary
+            .as_ref()
+            .is_some_and(|vocabulary| *vocabulary != fingerprint)
+        {
+            return Err(file_error(
+                path,
+                "saved with a model with a different vocabulary",
+            ));
+        }
+        let log = session
+            .log
+            .iter()
+            .map(|entry| {
+                if !self.store.contains(&entry.word) {
+                    return Err(CommandError::UnknownWord(entry.word.clone()));
+                }
+                let mut constraint =
+                    Constraint::parse(&entry.word, &entry.similarity, entry.tolerance).ok_or_else(
+                        || file_error(path, format!("invalid similarity {}", entry.similarity)),
+                    )?;
+                constraint.rank = match &entry.rank {
+                    Some(rank) => Some(
+                        Rank::parse(rank)
+                            .ok_or_else(|| file_error(path, format!("invalid rank {rank}")))?,
+                    ),
+                    None => None,
+                };
+                Ok(constraint)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((session, log))
+    }
+
+    /// Switches to a session from [`read`](Self::read), returning the model
+    /// it was saved with if that is not the loaded one.
+    fn apply(&mut self, (session, log): (Session, Vec<Constraint>)) -> Option<PathBuf> {
+        self.settings.tolerance = session.tolerance;
+        self.settings.rank_threshold = session.rank_threshold;
+        self.matches = log
+            .iter()
+            .map(|constraint| self.matching(constraint))
+            .collect();
+        self.log = log;
+        self.update_words();
+        let saved = session.model.path?;
+        let loaded = self.store.path()?;
+        (!same_file(&saved, loaded)).then_some(saved)
+    }
+}
+
+/// Whether two paths name the same file, comparing them as given if either
+/// can't be resolved.
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn file_error(path: &Path, reason: impl ToString) -> CommandError {
+    CommandError::File {
+        path: path.to_path_buf(),
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::commands::Word;
+    use crate::solver::tests::settings;
+    use crate::solver::tests::store;
+    use crate::solver::tests::store_of;
+    use crate::solver::SolverCommand;
+    use crate::EmbeddingStore;
+
+    fn session_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("semantle-{}-{name}.json", std::process::id()))
+    }
+
+    #[test]
+    fn sessions_round_trip() {
+        let store = store();
+        let path = session_file("round-trip");
+        let mut state = SolverState::new(&store, settings());
+        Word.run(&["w1", "12.34"], &mut state).unwrap();
+        state.save(&path).unwrap();
+
+        let mut resumed = SolverState::new(&store, settings());
+        assert_eq!(resumed.resume(&path), Ok(None));
+        assert_eq!(resumed.log, state.log);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rejects_a_different_vocabulary() {
+        let store = store();
+        let path = session_file("vocabulary");
+        SolverState::new(&store, settings()).save(&path).unwrap();
+
+        let other = store_of(200);
+        let words = (0..200).map(|i| format!("x{i}")).collect();
+        let other = EmbeddingStore::from_parts(words, other.matrix().to_owned()).unwrap();
+        let mut state = SolverState::new(&other, settings());
+        assert!(state.resume(&path).is_err());
+        fs::remove_file(path).unwrap();
+    }
+}